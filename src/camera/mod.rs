@@ -0,0 +1,455 @@
+// pour le bloom : https://github.com/bevyengine/bevy/blob/latest/examples/2d/bloom_2d.rs
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_pixel_camera::{PixelCameraPlugin, PixelViewport, PixelZoom};
+use bevy_rapier2d::prelude::Velocity;
+
+use bevy::core_pipeline::{bloom::BloomSettings, tonemapping::Tonemapping};
+
+use crate::config::GameState;
+use crate::player::{Direction, Player};
+
+mod subpixel;
+mod zone;
+pub use subpixel::CameraMode;
+use zone::CameraZone;
+
+/// Taille de la vue en pixels du jeu, avant le zoom des zones de caméra
+const VIEW_WIDTH: f32 = 320.0;
+const VIEW_HEIGHT: f32 = 180.0;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(PixelCameraPlugin)
+            .init_resource::<CameraMode>()
+            .init_resource::<subpixel::SubpixelOffset>()
+            .init_resource::<CameraBounds>()
+            .init_resource::<CameraShake>()
+            .init_resource::<CameraShakeSettings>()
+            .add_event::<CameraShakeEvent>()
+            .register_type::<PlayerCamera>()
+            .register_type::<CameraShakeSettings>()
+            .configure_sets(
+                PostUpdate,
+                (CameraSystem::Follow, CameraSystem::Offset)
+                    .chain()
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(Startup, setup_camera)
+            .add_systems(
+                PreUpdate,
+                (
+                    subpixel::restore_camera.run_if(resource_equals(CameraMode::Subpixel)),
+                    remove_camera_shake,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_map_bounds,
+                    (zone::setup_camera_zones, zone::update_camera_zones).chain(),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    camera_follow
+                        .run_if(in_state(GameState::Playing))
+                        .in_set(CameraSystem::Follow),
+                    (
+                        apply_camera_shake,
+                        subpixel::snap_camera.run_if(resource_equals(CameraMode::Subpixel)),
+                    )
+                        .chain()
+                        .in_set(CameraSystem::Offset),
+                ),
+            );
+    }
+}
+
+/// Les systèmes qui déplacent la caméra doivent être dans Follow, le tremblement est appliqué
+/// par dessus une fois qu'ils ont tous tourné
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CameraSystem {
+    Follow,
+    /// Décalages temporaires (tremblement, arrondi au pixel) retirés au début de la frame
+    Offset,
+}
+
+/// Réglages globaux du tremblement, `enabled` sert d'option d'accessibilité, modifiable depuis
+/// l'éditeur (bevy_editor_pls)
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct CameraShakeSettings {
+    pub enabled: bool,
+    /// Décalage maximal en pixels pour un trauma de 1
+    pub max_offset: f32,
+}
+
+impl Default for CameraShakeSettings {
+    fn default() -> Self {
+        CameraShakeSettings {
+            enabled: true,
+            max_offset: 6.0,
+        }
+    }
+}
+
+/// Demande un tremblement de caméra
+#[derive(Event, Clone, Copy)]
+pub struct CameraShakeEvent {
+    /// Trauma ajouté, entre 0 et 1, le décalage est proportionnel au carré du trauma
+    pub intensity: f32,
+    /// Temps pour que le trauma redescende a 0, en secondes
+    pub duration: f32,
+    /// Nombre d'oscillations par seconde
+    pub frequency: f32,
+    /// Direction privilégiée du tremblement, Vec2::ZERO pour trembler dans toutes les directions
+    pub direction: Vec2,
+}
+
+impl CameraShakeEvent {
+    pub fn new(intensity: f32, duration: f32) -> CameraShakeEvent {
+        CameraShakeEvent {
+            intensity,
+            duration,
+            frequency: 25.0,
+            direction: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_direction(mut self, direction: Vec2) -> CameraShakeEvent {
+        self.direction = direction.normalize_or_zero();
+        self
+    }
+}
+
+/// État du tremblement en cours
+#[derive(Resource, Default)]
+pub struct CameraShake {
+    trauma: f32,
+    /// Trauma perdu par seconde
+    decay: f32,
+    frequency: f32,
+    direction: Vec2,
+    time: f32,
+    /// Décalage appliqué a la caméra cette frame, retiré au début de la frame suivante
+    offset: Vec2,
+}
+
+/// Limites dans lesquelles la vue de la caméra doit rester
+#[derive(Resource, Default)]
+pub struct CameraBounds {
+    /// Rectangle couvert par la tilemap
+    pub map: Option<Rect>,
+    /// Rectangle de la salle active, prioritaire sur la map
+    pub room: Option<Rect>,
+}
+
+impl CameraBounds {
+    pub fn current(&self) -> Option<Rect> {
+        self.room.or(self.map)
+    }
+}
+
+/// Position de la caméra qui garde la vue dans les limites, les limites sont centrées si elles
+/// sont plus petites que la vue
+pub fn clamp_to_bounds(bounds: Rect, target: Vec2, half_view: Vec2) -> Vec2 {
+    let clamp_axis = |value: f32, min: f32, max: f32, half: f32| {
+        if max - min <= half * 2.0 {
+            (min + max) / 2.0
+        } else {
+            value.clamp(min + half, max - half)
+        }
+    };
+
+    Vec2::new(
+        clamp_axis(target.x, bounds.min.x, bounds.max.x, half_view.x),
+        clamp_axis(target.y, bounds.min.y, bounds.max.y, half_view.y),
+    )
+}
+
+/// Caméra qui suit le joueur, tous les réglages sont modifiables depuis l'inspecteur
+#[derive(Component, Reflect)]
+pub struct PlayerCamera {
+    /// Demi-taille de la zone dans laquelle le joueur peut bouger sans que la caméra le suive
+    pub deadzone: Vec2,
+    /// Décalage maximal de la caméra devant le joueur
+    pub look_ahead_distance: f32,
+    /// Vitesse horizontale du joueur a partir de laquelle le décalage est maximal
+    pub look_ahead_velocity: f32,
+    /// Vitesse a laquelle le décalage rattrape sa cible, en 1/s
+    pub look_ahead_smoothing: f32,
+    /// La caméra ne suit pas le joueur verticalement tant qu'il est en l'air, sauf s'il sort de
+    /// la deadzone
+    pub vertical_lock: bool,
+    /// Vitesse a laquelle la caméra rattrape sa cible, en 1/s, indépendant du framerate
+    pub smoothing: f32,
+
+    /// Point suivi par la caméra, déplacé par le joueur quand il sort de la deadzone
+    focus: Option<Vec2>,
+    look_ahead: f32,
+}
+
+impl Default for PlayerCamera {
+    fn default() -> Self {
+        PlayerCamera {
+            deadzone: Vec2::new(8.0, 16.0),
+            look_ahead_distance: 24.0,
+            look_ahead_velocity: 150.0,
+            look_ahead_smoothing: 3.0,
+            vertical_lock: true,
+            smoothing: 4.0,
+
+            focus: None,
+            look_ahead: 0.0,
+        }
+    }
+}
+
+/// Facteur d'interpolation exponentielle, donne le même résultat quel que soit le framerate
+fn smoothing_factor(rate: f32, delta: f32) -> f32 {
+    1.0 - (-rate * delta).exp()
+}
+
+fn setup_camera(mut commands: Commands, mode: Res<CameraMode>, mut images: ResMut<Assets<Image>>) {
+    match *mode {
+        CameraMode::PixelViewport => {
+            commands.spawn((
+                Camera2dBundle {
+                    camera: Camera {
+                        hdr: true,
+                        ..default()
+                    },
+                    tonemapping: Tonemapping::AcesFitted,
+                    ..default()
+                },
+                BloomSettings::default(),
+                PixelZoom::FitSize {
+                    width: VIEW_WIDTH as i32,
+                    height: VIEW_HEIGHT as i32,
+                },
+                PixelViewport,
+                PlayerCamera::default(),
+                // PickRaycastSource,
+            ));
+        }
+        CameraMode::Subpixel => {
+            let image = subpixel::create_render_target(&mut images);
+
+            // la projection par défaut fait correspondre un pixel de la texture a une unité du
+            // monde, comme PixelZoom le fait pour la fenêtre
+            commands.spawn((
+                Camera2dBundle {
+                    camera: subpixel::game_camera_target(image.clone()),
+                    tonemapping: Tonemapping::AcesFitted,
+                    ..default()
+                },
+                BloomSettings::default(),
+                PlayerCamera::default(),
+            ));
+            subpixel::spawn_presentation(&mut commands, image);
+        }
+    }
+}
+
+/// Calcule le rectangle couvert par les calques de la tilemap
+fn update_map_bounds(
+    mut bounds: ResMut<CameraBounds>,
+    layers: Query<(&TilemapSize, &TilemapGridSize, &Transform)>,
+) {
+    let mut map: Option<Rect> = None;
+
+    for (size, grid_size, transform) in &layers {
+        // le transform d'une couche place le centre de sa première tuile, on recule d'une demi
+        // tuile pour avoir le coin de la map (les couches infinies ne sont pas centrées)
+        let min = transform.translation.truncate() - Vec2::new(grid_size.x, grid_size.y) / 2.0;
+        let max = min + Vec2::new(size.x as f32 * grid_size.x, size.y as f32 * grid_size.y);
+        let rect = Rect::from_corners(min, max);
+
+        map = Some(match map {
+            Some(map) => map.union(rect),
+            None => rect,
+        });
+    }
+
+    if bounds.map != map {
+        bounds.map = map;
+    }
+}
+
+fn camera_follow(
+    query: Query<(&Transform, &Player, &Direction, &Velocity)>,
+    bounds: Res<CameraBounds>,
+    time: Res<Time>,
+    zones: Query<&CameraZone>,
+    // les query permettent de récupérer des components d'entités dans le World
+    // c'est un générique avec 2 parties :
+    // - les éléments qu'on veut query
+    // - les filtres
+    // doc des queries : https://bevy-cheatbook.github.io/programming/queries.html
+    mut camera: Query<
+        (
+            &mut Transform,
+            &mut PlayerCamera,
+            &mut OrthographicProjection,
+        ),
+        Without<Player>,
+    >,
+) {
+    // on peut gérer les queries de plusieurs manières différentes :
+    // - on peut itérer, safe car si elle est vide elle n'itère pas (mais ne crash pas), très
+    // utile quand il y a plusieurs éléments à query
+    // - on peut query.single() qui renvoie l'élément uniquement si il existe un seul élément query
+    // dans le monde, panic autrement
+    // - on peut query.single_mut() qui fait la même mais renvoie la/les ref mutable(s)
+    // - on peut query.get_single(), pareil que single mais renvoie un Option<T>, on doit alors
+    // gérer le cas ou la valeur est None, ne panic pas
+    // - on peut query.get_single_mut()
+    // - on peut faire comme ci dessous :
+    if query.is_empty() {
+        return;
+    }
+
+    // le query.single() ne peut pas panic car on return si la query est empty
+    let (player, player_data, direction, velocity) = query.single();
+    let player_position = player.translation.truncate();
+
+    let (mut transform, mut camera, mut projection) = camera.single_mut();
+    let delta = time.delta_seconds();
+
+    // deadzone : le point suivi ne bouge que quand le joueur pousse sur les bords de la zone
+    let mut focus = camera.focus.unwrap_or(player_position);
+    let deadzone = camera.deadzone;
+
+    if player_position.x > focus.x + deadzone.x {
+        focus.x = player_position.x - deadzone.x;
+    } else if player_position.x < focus.x - deadzone.x {
+        focus.x = player_position.x + deadzone.x;
+    }
+
+    if camera.vertical_lock && !player_data.is_grounded() {
+        // en l'air on ne suit que si le joueur sort de la deadzone
+        if player_position.y > focus.y + deadzone.y {
+            focus.y = player_position.y - deadzone.y;
+        } else if player_position.y < focus.y - deadzone.y {
+            focus.y = player_position.y + deadzone.y;
+        }
+    } else {
+        // au sol la caméra se recale sur le joueur
+        focus.y = player_position.y;
+    }
+    camera.focus = Some(focus);
+
+    // look-ahead : la caméra regarde devant le joueur, d'autant plus qu'il va vite
+    let facing = match direction {
+        Direction::Right => 1.0,
+        Direction::Left => -1.0,
+    };
+    let speed_ratio = (velocity.linvel.x.abs() / camera.look_ahead_velocity).clamp(0.0, 1.0);
+    let look_ahead_target = facing * camera.look_ahead_distance * speed_ratio;
+    camera.look_ahead += (look_ahead_target - camera.look_ahead)
+        * smoothing_factor(camera.look_ahead_smoothing, delta);
+
+    let follow = focus + Vec2::new(camera.look_ahead, 0.0);
+
+    // les zones de caméra de Tiled modifient la cible et le zoom
+    let zone = zone::blend_zones(follow, zones.iter());
+    let mut target = zone.target;
+
+    // le zoom passe par l'échelle de la projection dans les deux modes, PixelZoom ne donne que
+    // des zooms entiers et ferait avancer le zoom par paliers. L'aire de la projection n'est
+    // recalculée qu'après ce système, on la ramène a l'échelle de cette frame pour le clamp
+    let half_view = projection.area.half_size() / projection.scale * zone.zoom;
+    if projection.scale != zone.zoom {
+        projection.scale = zone.zoom;
+    }
+
+    // on vise une position qui garde la vue dans la map ou la salle
+    if let Some(bounds) = bounds.current() {
+        target = clamp_to_bounds(bounds, target, half_view);
+    }
+
+    // on ne touche pas au z de la caméra, sinon elle se rapproche du plan du joueur
+    let position = transform.translation.truncate();
+    let position = position + (target - position) * smoothing_factor(camera.smoothing, delta);
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+}
+
+/// Retire le décalage de la frame précédente pour que les systèmes de suivi travaillent sur la
+/// vraie position de la caméra
+fn remove_camera_shake(
+    mut shake: ResMut<CameraShake>,
+    mut camera: Query<&mut Transform, With<PlayerCamera>>,
+) {
+    if shake.offset == Vec2::ZERO {
+        return;
+    }
+
+    for mut transform in &mut camera {
+        transform.translation.x -= shake.offset.x;
+        transform.translation.y -= shake.offset.y;
+    }
+    shake.offset = Vec2::ZERO;
+}
+
+/// Bruit lisse entre -1 et 1, somme de sinus a des fréquences non multiples
+fn shake_noise(time: f32, seed: f32) -> f32 {
+    ((time * TAU + seed).sin() + (time * TAU * 2.3 + seed * 1.7).sin() * 0.5) / 1.5
+}
+
+fn apply_camera_shake(
+    time: Res<Time>,
+    settings: Res<CameraShakeSettings>,
+    mut shake: ResMut<CameraShake>,
+    mut events: EventReader<CameraShakeEvent>,
+    mut camera: Query<&mut Transform, With<PlayerCamera>>,
+) {
+    for event in events.read() {
+        shake.trauma = (shake.trauma + event.intensity).min(1.0);
+        shake.decay = shake.trauma / event.duration.max(0.01);
+        shake.frequency = event.frequency;
+        shake.direction = event.direction;
+    }
+
+    if shake.trauma <= 0.0 {
+        return;
+    }
+
+    let delta = time.delta_seconds();
+    shake.time += delta;
+    shake.trauma = (shake.trauma - shake.decay * delta).max(0.0);
+
+    if !settings.enabled {
+        return;
+    }
+
+    let amount = shake.trauma * shake.trauma * settings.max_offset;
+    let t = shake.time * shake.frequency;
+    let noise = Vec2::new(shake_noise(t, 0.0), shake_noise(t, 42.0));
+
+    let offset = if shake.direction == Vec2::ZERO {
+        noise * amount
+    } else {
+        // la plus grosse partie du tremblement suit la direction demandée
+        let perpendicular = shake.direction.perp();
+        (shake.direction * noise.x + perpendicular * noise.y * 0.25) * amount
+    };
+
+    // on reste sur des pixels entiers pour ne pas casser le rendu pixel perfect
+    let offset = offset.round();
+
+    for mut transform in &mut camera {
+        transform.translation.x += offset.x;
+        transform.translation.y += offset.y;
+    }
+    shake.offset = offset;
+}
@@ -0,0 +1,155 @@
+// rendu en basse résolution dans une texture puis affichage de cette texture agrandie avec un
+// décalage inférieur au pixel : les sprites restent alignés sur la grille de pixels du jeu mais
+// le défilement de la caméra est fluide a la résolution de l'écran
+//
+// la caméra du jeu (PlayerCamera) est arrondie au pixel près avant le rendu, la partie
+// fractionnaire de sa position est appliquée au sprite qui affiche la texture
+
+use bevy::prelude::*;
+use bevy::render::{
+    camera::RenderTarget,
+    render_resource::{
+        Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+    },
+    view::RenderLayers,
+};
+use bevy_pixel_camera::{PixelViewport, PixelZoom};
+
+use crate::camera::{PlayerCamera, VIEW_HEIGHT, VIEW_WIDTH};
+
+/// Pixels ajoutés autour de la texture pour ne pas voir le bord pendant le décalage
+const MARGIN: u32 = 1;
+
+/// Calque de rendu de la caméra de présentation, le jeu reste sur le calque 0
+const PRESENTATION_LAYER: u8 = 1;
+
+/// Méthode de rendu de la caméra, choisie par le ConfigPlugin (argument `--subpixel`)
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CameraMode {
+    /// La caméra rend directement dans la fenêtre avec bevy_pixel_camera
+    #[default]
+    PixelViewport,
+    /// La caméra rend dans une texture basse résolution affichée avec un décalage sous-pixel
+    Subpixel,
+}
+
+/// Caméra qui affiche la texture du jeu dans la fenêtre
+#[derive(Component)]
+pub struct PresentationCamera;
+
+/// Sprite qui porte la texture du jeu
+#[derive(Component)]
+pub struct PresentationSprite;
+
+/// Partie fractionnaire de la position de la caméra retirée avant le rendu
+#[derive(Resource, Default)]
+pub struct SubpixelOffset(Vec2);
+
+/// Crée la texture dans laquelle le jeu est rendu
+pub fn create_render_target(images: &mut Assets<Image>) -> Handle<Image> {
+    let size = Extent3d {
+        width: VIEW_WIDTH as u32 + MARGIN * 2,
+        height: VIEW_HEIGHT as u32 + MARGIN * 2,
+        depth_or_array_layers: 1,
+    };
+
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("low resolution game view"),
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..Default::default()
+    };
+    // remplit la texture de zéros
+    image.resize(size);
+
+    images.add(image)
+}
+
+/// Cible de rendu de la caméra du jeu en mode sous-pixel
+pub fn game_camera_target(image: Handle<Image>) -> Camera {
+    Camera {
+        hdr: true,
+        // rendu avant la caméra de présentation
+        order: -1,
+        target: RenderTarget::Image(image),
+        ..Default::default()
+    }
+}
+
+/// Fait spawn la caméra et le sprite qui affichent la texture dans la fenêtre
+pub fn spawn_presentation(commands: &mut Commands, image: Handle<Image>) {
+    let layer = RenderLayers::layer(PRESENTATION_LAYER);
+
+    commands.spawn((
+        Name::new("Presentation sprite"),
+        SpriteBundle {
+            texture: image,
+            ..Default::default()
+        },
+        PresentationSprite,
+        layer,
+    ));
+
+    commands.spawn((
+        Name::new("Presentation camera"),
+        Camera2dBundle::default(),
+        // même zoom entier que la caméra pixel perfect, la marge est coupée par le viewport
+        PixelZoom::FitSize {
+            width: VIEW_WIDTH as i32,
+            height: VIEW_HEIGHT as i32,
+        },
+        PixelViewport,
+        PresentationCamera,
+        layer,
+    ));
+}
+
+/// Arrondit la caméra du jeu au pixel et décale le sprite de présentation de la différence
+pub fn snap_camera(
+    mut offset: ResMut<SubpixelOffset>,
+    mut camera: Query<&mut Transform, With<PlayerCamera>>,
+    mut sprite: Query<&mut Transform, (With<PresentationSprite>, Without<PlayerCamera>)>,
+) {
+    let Ok(mut transform) = camera.get_single_mut() else {
+        return;
+    };
+
+    let position = transform.translation.truncate();
+    let snapped = position.round();
+    offset.0 = position - snapped;
+
+    transform.translation.x = snapped.x;
+    transform.translation.y = snapped.y;
+
+    // la caméra est en retard de la partie fractionnaire, l'image doit donc glisser dans
+    // l'autre sens
+    for mut sprite_transform in &mut sprite {
+        sprite_transform.translation.x = -offset.0.x;
+        sprite_transform.translation.y = -offset.0.y;
+    }
+}
+
+/// Remet la partie fractionnaire pour que le suivi de caméra reste fluide
+pub fn restore_camera(
+    mut offset: ResMut<SubpixelOffset>,
+    mut camera: Query<&mut Transform, With<PlayerCamera>>,
+) {
+    if offset.0 == Vec2::ZERO {
+        return;
+    }
+
+    for mut transform in &mut camera {
+        transform.translation.x += offset.0.x;
+        transform.translation.y += offset.0.y;
+    }
+    offset.0 = Vec2::ZERO;
+}
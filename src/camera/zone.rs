@@ -0,0 +1,158 @@
+// zones de caméra posées dans un calque d'objets de Tiled (classe "camera_zone" ou calque
+// "camera_zones"), tant que le joueur est dedans elles modifient la cible de la caméra
+//
+// propriétés reconnues :
+// - lock (string) : "x", "y" ou "both", bloque la caméra au centre de la zone sur cet axe
+// - fixed (bool) : la caméra vise le centre de la zone
+// - target (object) : la caméra vise cet objet (un point par exemple)
+// - offset_x, offset_y (float) : décalage ajouté a la cible
+// - zoom (float) : facteur appliqué a la taille de la vue, 2.0 montre deux fois plus de décor
+// - blend (float) : durée du fondu en entrée et en sortie de zone, en secondes
+
+use bevy::prelude::*;
+
+use crate::player::Player;
+use crate::tile::objects::{object_rect, TiledObject};
+
+/// Durée du fondu si la zone n'en précise pas
+const DEFAULT_BLEND_TIME: f32 = 0.5;
+
+#[derive(Component)]
+pub struct CameraZone {
+    pub lock_x: bool,
+    pub lock_y: bool,
+    pub fixed: bool,
+    /// Id de l'objet Tiled visé par la caméra
+    pub target_object: Option<u32>,
+    pub offset: Vec2,
+    pub zoom: f32,
+    pub blend_time: f32,
+
+    /// Position visée, résolue depuis target_object ou le centre de la zone
+    target: Option<Vec2>,
+    center: Vec2,
+    /// Influence de la zone entre 0 et 1, monte quand le joueur est dedans
+    weight: f32,
+}
+
+impl CameraZone {
+    fn from_object(object: &TiledObject) -> CameraZone {
+        let lock = object.string_property("lock").unwrap_or_default();
+
+        CameraZone {
+            lock_x: lock.eq_ignore_ascii_case("x") || lock.eq_ignore_ascii_case("both"),
+            lock_y: lock.eq_ignore_ascii_case("y") || lock.eq_ignore_ascii_case("both"),
+            fixed: object.bool_property("fixed").unwrap_or(false),
+            target_object: object.object_property("target"),
+            offset: Vec2::new(
+                object.float_property("offset_x").unwrap_or(0.0),
+                object.float_property("offset_y").unwrap_or(0.0),
+            ),
+            zoom: object.float_property("zoom").unwrap_or(1.0).max(0.1),
+            blend_time: object.float_property("blend").unwrap_or(DEFAULT_BLEND_TIME),
+
+            target: None,
+            center: Vec2::ZERO,
+            weight: 0.0,
+        }
+    }
+
+    /// Cible de la caméra dans la zone a partir de la cible du suivi normal
+    fn apply(&self, follow: Vec2) -> Vec2 {
+        let mut target = match (self.fixed, self.target) {
+            (_, Some(target)) => target,
+            (true, None) => self.center,
+            (false, None) => follow,
+        };
+
+        if self.lock_x {
+            target.x = self.center.x;
+        }
+        if self.lock_y {
+            target.y = self.center.y;
+        }
+
+        target + self.offset
+    }
+}
+
+/// Résultat du mélange de toutes les zones actives
+pub struct ZoneOverride {
+    pub target: Vec2,
+    pub zoom: f32,
+}
+
+/// Mélange la cible de suivi avec les zones dans lesquelles le joueur est (ou vient de sortir)
+pub fn blend_zones<'a>(follow: Vec2, zones: impl Iterator<Item = &'a CameraZone>) -> ZoneOverride {
+    let mut result = ZoneOverride {
+        target: follow,
+        zoom: 1.0,
+    };
+
+    for zone in zones.filter(|zone| zone.weight > 0.0) {
+        // smoothstep pour que le fondu démarre et s'arrête en douceur
+        let weight = zone.weight * zone.weight * (3.0 - 2.0 * zone.weight);
+
+        result.target = result.target.lerp(zone.apply(result.target), weight);
+        result.zoom += (zone.zoom - result.zoom) * weight;
+    }
+
+    result
+}
+
+pub fn setup_camera_zones(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledObject), Added<TiledObject>>,
+) {
+    for (entity, object) in &query {
+        if object.is("camera_zone") || object.is("camera_zones") {
+            commands
+                .entity(entity)
+                .insert(CameraZone::from_object(object));
+        }
+    }
+}
+
+/// Fait monter ou descendre l'influence des zones selon la présence du joueur
+pub fn update_camera_zones(
+    time: Res<Time>,
+    player: Query<&Transform, With<Player>>,
+    objects: Query<(&TiledObject, &GlobalTransform)>,
+    mut zones: Query<(&mut CameraZone, &TiledObject, &GlobalTransform)>,
+) {
+    if player.is_empty() {
+        return;
+    }
+
+    let player_position = player.single().translation.truncate();
+
+    for (mut zone, object, transform) in &mut zones {
+        let rect = object_rect(object, transform);
+        zone.center = rect.center();
+
+        if zone.target.is_none() {
+            if let Some(id) = zone.target_object {
+                zone.target = objects
+                    .iter()
+                    .find(|(target, _)| target.id == id)
+                    .map(|(_, transform)| transform.translation().truncate());
+            }
+        }
+
+        let step = if zone.blend_time > 0.0 {
+            time.delta_seconds() / zone.blend_time
+        } else {
+            1.0
+        };
+
+        let weight = if rect.contains(player_position) {
+            (zone.weight + step).min(1.0)
+        } else {
+            (zone.weight - step).max(0.0)
+        };
+
+        if zone.weight != weight {
+            zone.weight = weight;
+        }
+    }
+}
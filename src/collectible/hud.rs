@@ -0,0 +1,72 @@
+// affichage du nombre de fraises : celles du niveau actuel sur le total du niveau, puis celles
+// ramassées dans les autres niveaux
+
+use bevy::prelude::*;
+
+use crate::collectible::{Collectible, Collection};
+use crate::tile::LevelManager;
+
+#[derive(Component)]
+pub struct CollectibleHud;
+
+pub fn spawn_hud(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Collectible HUD"),
+        CollectibleHud,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..Default::default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..Default::default()
+        }),
+    ));
+}
+
+pub fn update_hud(
+    levels: Res<LevelManager>,
+    collection: Res<Collection>,
+    collectibles: Query<&Collectible>,
+    mut hud: Query<&mut Text, With<CollectibleHud>>,
+) {
+    if hud.is_empty() {
+        return;
+    }
+
+    let mut lines = Vec::new();
+
+    if let Some(current) = levels.current() {
+        let total = collectibles
+            .iter()
+            .filter(|collectible| collectible.level == current)
+            .count();
+
+        if total > 0 {
+            lines.push(format!(
+                "Strawberries: {}/{total}",
+                collection.count(current)
+            ));
+        }
+    }
+
+    for (level, count) in collection.levels() {
+        if Some(level) != levels.current() {
+            lines.push(format!("{level}: {count}"));
+        }
+    }
+
+    let value = lines.join("\n");
+    let mut text = hud.single_mut();
+
+    // évite de marquer le texte comme modifié a chaque frame
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
@@ -0,0 +1,310 @@
+// fraises a ramasser, placées dans Tiled (classe "strawberry" ou calque "strawberries")
+//
+// quand le joueur touche une fraise elle le suit, elle n'est ramassée qu'une fois que le joueur
+// est resté un petit moment au sol, s'il meurt avant elle retourne a sa place
+//
+// les fraises ramassées sont retenues par niveau avec l'id de leur objet Tiled et sauvegardées,
+// une fraise déjà ramassée apparait transparente et peut être reprise sans compter deux fois
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::config::GameState;
+use crate::mechanics::crumble::CrumbleSolid;
+use crate::mechanics::spring::{Bumper, Spring};
+use crate::mechanics::{MovingPlatform, TriggerEvent};
+use crate::particles::{ParticleBurst, ParticlePreset};
+use crate::player::{Direction, Ground, Launched, Player, PlayerDied};
+use crate::room::{ActiveRoom, InRoom};
+use crate::tile::objects::TiledObject;
+use crate::tile::LevelManager;
+
+mod hud;
+mod save;
+
+pub use save::Collection;
+
+/// Temps que le joueur doit passer au sol pour ramasser les fraises qui le suivent
+const COLLECT_DELAY: f32 = 0.3;
+/// Position de la première fraise par rapport au joueur quand il regarde a droite
+const FOLLOW_OFFSET: Vec2 = Vec2::new(-10.0, 8.0);
+/// Écart horizontal entre les fraises de la file
+const FOLLOW_SPACING: f32 = 10.0;
+/// Plus la valeur est grande plus les fraises rattrapent vite leur place
+const FOLLOW_SPEED: f32 = 8.0;
+const SIZE: f32 = 8.0;
+
+const COLOR: Color = Color::rgb(0.9, 0.2, 0.3);
+const COLLECTED_COLOR: Color = Color::rgba(0.9, 0.2, 0.3, 0.4);
+
+pub struct CollectiblePlugin;
+
+impl Plugin for CollectiblePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Collection>()
+            .add_systems(Startup, (save::load_collection, hud::spawn_hud))
+            .add_systems(
+                Update,
+                (
+                    setup_collectibles,
+                    return_collectibles,
+                    (touch_collectibles, follow_player, collect_collectibles)
+                        .run_if(in_state(GameState::Playing)),
+                    save::write_collection,
+                    hud::update_hud,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CollectibleState {
+    Idle,
+    /// Suit le joueur, l'index est la place dans la file
+    Following(usize),
+    Collected,
+}
+
+#[derive(Component)]
+pub struct Collectible {
+    pub id: u32,
+    /// Niveau qui contient la fraise
+    pub level: String,
+    pub state: CollectibleState,
+    /// Position de départ, relative au calque d'objets
+    home: Vec3,
+}
+
+pub fn setup_collectibles(
+    mut commands: Commands,
+    levels: Res<LevelManager>,
+    collection: Res<Collection>,
+    query: Query<(Entity, &TiledObject, &Transform), Added<TiledObject>>,
+) {
+    for (entity, object, transform) in &query {
+        if !object.is("strawberry") && !object.is("strawberries") {
+            continue;
+        }
+
+        let Some(level) = levels.current() else {
+            continue;
+        };
+
+        let color = if collection.contains(level, object.id) {
+            COLLECTED_COLOR
+        } else {
+            COLOR
+        };
+
+        commands.entity(entity).insert((
+            Collectible {
+                id: object.id,
+                level: level.to_string(),
+                state: CollectibleState::Idle,
+                home: transform.translation,
+            },
+            Collider::ball(SIZE / 2.0),
+            Sensor,
+            ActiveEvents::COLLISION_EVENTS,
+            Sprite {
+                color,
+                custom_size: Some(Vec2::splat(SIZE)),
+                ..Default::default()
+            },
+            Handle::<Image>::default(),
+        ));
+    }
+}
+
+/// Le joueur attrape les fraises qu'il touche, elles se mettent au bout de la file
+pub fn touch_collectibles(
+    mut collisions: EventReader<CollisionEvent>,
+    player: Query<(), With<Player>>,
+    mut collectibles: Query<&mut Collectible>,
+) {
+    let mut following = collectibles
+        .iter()
+        .filter(|collectible| matches!(collectible.state, CollectibleState::Following(_)))
+        .count();
+
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = collision else {
+            continue;
+        };
+
+        let other = match (player.contains(*first), player.contains(*second)) {
+            (true, _) => *second,
+            (_, true) => *first,
+            _ => continue,
+        };
+
+        let Ok(mut collectible) = collectibles.get_mut(other) else {
+            continue;
+        };
+
+        if collectible.state == CollectibleState::Idle {
+            collectible.state = CollectibleState::Following(following);
+            following += 1;
+        }
+    }
+}
+
+/// Les fraises attrapées glissent derrière le joueur et le suivent de salle en salle
+pub fn follow_player(
+    time: Res<Time>,
+    active_room: Res<ActiveRoom>,
+    player: Query<(&Transform, &Direction), With<Player>>,
+    mut collectibles: Query<
+        (&Collectible, &Parent, &mut Transform, Option<&mut InRoom>),
+        Without<Player>,
+    >,
+    parents: Query<&GlobalTransform>,
+) {
+    if player.is_empty() {
+        return;
+    }
+
+    let (player_transform, direction) = player.single();
+    let facing = match direction {
+        Direction::Right => 1.0,
+        Direction::Left => -1.0,
+    };
+    // lissage indépendant du framerate
+    let t = 1.0 - (-FOLLOW_SPEED * time.delta_seconds()).exp();
+
+    for (collectible, parent, mut transform, in_room) in &mut collectibles {
+        let CollectibleState::Following(index) = collectible.state else {
+            continue;
+        };
+
+        let Ok(parent_transform) = parents.get(parent.get()) else {
+            continue;
+        };
+
+        let offset = Vec2::new(
+            (FOLLOW_OFFSET.x - FOLLOW_SPACING * index as f32) * facing,
+            FOLLOW_OFFSET.y,
+        );
+        let target = player_transform.translation.truncate() + offset;
+
+        // la fraise est enfant de son calque, on passe par la position dans le monde
+        let current = parent_transform.transform_point(transform.translation);
+        let position = current.truncate().lerp(target, t).extend(current.z);
+        let local = parent_transform
+            .affine()
+            .inverse()
+            .transform_point3(position);
+
+        transform.translation.x = local.x;
+        transform.translation.y = local.y;
+
+        // la fraise reste dans la salle du joueur pour ne pas être mise en pause avec l'ancienne
+        if let (Some(mut in_room), Some(room)) = (in_room, active_room.0) {
+            if in_room.0 != room {
+                in_room.0 = room;
+            }
+        }
+    }
+}
+
+/// Ramasse les fraises qui suivent le joueur quand il reste assez longtemps sur un sol sûr : pas
+/// un bloc qui s'effrite, une plateforme mobile ou juste après un ressort. Lance les événements
+/// "collected" et "all_collected" pour les déclencheurs
+#[allow(clippy::type_complexity)]
+pub fn collect_collectibles(
+    time: Res<Time>,
+    mut grounded_time: Local<f32>,
+    mut collection: ResMut<Collection>,
+    mut bursts: EventWriter<ParticleBurst>,
+    mut events: EventWriter<TriggerEvent>,
+    player: Query<(&Player, Option<&Ground>, Has<Launched>)>,
+    unsafe_ground: Query<
+        (),
+        Or<(
+            With<CrumbleSolid>,
+            With<MovingPlatform>,
+            With<Spring>,
+            With<Bumper>,
+        )>,
+    >,
+    mut collectibles: Query<(&mut Collectible, &GlobalTransform, &mut Visibility)>,
+) {
+    if player.is_empty() {
+        return;
+    }
+
+    let (player, ground, launched) = player.single();
+    let safe = player.is_grounded()
+        && !launched
+        && ground.is_some_and(|ground| !unsafe_ground.contains(ground.entity));
+
+    if safe {
+        *grounded_time += time.delta_seconds();
+    } else {
+        *grounded_time = 0.0;
+    }
+
+    if *grounded_time < COLLECT_DELAY {
+        return;
+    }
+
+    let mut collected_any = false;
+
+    for (mut collectible, transform, mut visibility) in &mut collectibles {
+        if !matches!(collectible.state, CollectibleState::Following(_)) {
+            continue;
+        }
+
+        collectible.state = CollectibleState::Collected;
+        collected_any = true;
+        events.send(TriggerEvent {
+            name: "collected".to_string(),
+        });
+        *visibility = Visibility::Hidden;
+
+        bursts.send(ParticleBurst::new(
+            ParticlePreset::OrbPickup,
+            transform.translation().truncate(),
+        ));
+
+        // pas de modification si la fraise avait déjà été ramassée, sinon la sauvegarde est
+        // réécrite pour rien
+        if !collection.contains(&collectible.level, collectible.id) {
+            collection.add(&collectible.level, collectible.id);
+        }
+    }
+
+    let all_collected = collectibles
+        .iter()
+        .all(|(collectible, ..)| collectible.state == CollectibleState::Collected);
+
+    if collected_any && all_collected {
+        events.send(TriggerEvent {
+            name: "all_collected".to_string(),
+        });
+    }
+}
+
+/// Les fraises qui suivaient le joueur retournent a leur place quand il meurt
+pub fn return_collectibles(
+    mut commands: Commands,
+    mut deaths: EventReader<PlayerDied>,
+    mut collectibles: Query<(Entity, &mut Collectible, &mut Transform)>,
+) {
+    if deaths.read().count() == 0 {
+        return;
+    }
+
+    for (entity, mut collectible, mut transform) in &mut collectibles {
+        if !matches!(collectible.state, CollectibleState::Following(_)) {
+            continue;
+        }
+
+        collectible.state = CollectibleState::Idle;
+        transform.translation = collectible.home;
+
+        // la salle sera recalculée depuis sa position de départ
+        commands.entity(entity).remove::<InRoom>();
+    }
+}
@@ -0,0 +1,132 @@
+// sauvegarde des fraises ramassées dans un fichier texte, une ligne par niveau avec le chemin
+// du niveau puis les ids des objets Tiled ramassés :
+//
+// map.tmx: 3 12 15
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::ErrorKind;
+
+use bevy::{log, prelude::*};
+
+/// Fichier de sauvegarde, relatif au dossier de lancement du jeu
+const SAVE_PATH: &str = "save.txt";
+
+/// Fraises ramassées dans chaque niveau
+#[derive(Resource, Default)]
+pub struct Collection {
+    levels: BTreeMap<String, BTreeSet<u32>>,
+}
+
+impl Collection {
+    pub fn contains(&self, level: &str, id: u32) -> bool {
+        self.levels.get(level).is_some_and(|ids| ids.contains(&id))
+    }
+
+    /// Retourne false si la fraise avait déjà été ramassée
+    pub fn add(&mut self, level: &str, id: u32) -> bool {
+        self.levels.entry(level.to_string()).or_default().insert(id)
+    }
+
+    /// Nombre de fraises ramassées dans un niveau
+    pub fn count(&self, level: &str) -> usize {
+        self.levels.get(level).map_or(0, |ids| ids.len())
+    }
+
+    /// Niveaux dans lesquels au moins une fraise a été ramassée, avec leur nombre de fraises
+    pub fn levels(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.levels
+            .iter()
+            .map(|(level, ids)| (level.as_str(), ids.len()))
+    }
+
+    fn parse(text: &str) -> Collection {
+        let mut collection = Collection::default();
+
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            // le chemin peut contenir des ':', on coupe sur le dernier
+            let Some((level, ids)) = line.rsplit_once(':') else {
+                log::warn!("Invalid save line: {line}");
+                continue;
+            };
+
+            for id in ids.split_whitespace() {
+                match id.parse() {
+                    Ok(id) => {
+                        collection.add(level.trim(), id);
+                    }
+                    Err(_) => log::warn!("Invalid collectible id in save: {id}"),
+                }
+            }
+        }
+
+        collection
+    }
+
+    fn serialize(&self) -> String {
+        self.levels
+            .iter()
+            .map(|(level, ids)| {
+                let ids: Vec<String> = ids.iter().map(u32::to_string).collect();
+                format!("{level}: {}\n", ids.join(" "))
+            })
+            .collect()
+    }
+}
+
+pub fn load_collection(mut collection: ResMut<Collection>) {
+    match fs::read_to_string(SAVE_PATH) {
+        Ok(text) => *collection = Collection::parse(&text),
+        // première partie
+        Err(error) if error.kind() == ErrorKind::NotFound => {}
+        Err(error) => log::warn!("Can't read save file {SAVE_PATH}: {error}"),
+    }
+}
+
+/// Réécrit la sauvegarde quand une fraise est ramassée
+pub fn write_collection(collection: Res<Collection>) {
+    if !collection.is_changed() || collection.is_added() {
+        return;
+    }
+
+    if let Err(error) = fs::write(SAVE_PATH, collection.serialize()) {
+        log::warn!("Can't write save file {SAVE_PATH}: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_levels() {
+        let collection = Collection::parse("map.tmx: 3 12 15\n\nlevels/c:2.tmx: 1\n");
+
+        assert_eq!(collection.count("map.tmx"), 3);
+        assert!(collection.contains("map.tmx", 12));
+        assert!(!collection.contains("map.tmx", 4));
+        assert!(collection.contains("levels/c:2.tmx", 1));
+    }
+
+    #[test]
+    fn parse_skips_invalid() {
+        let collection = Collection::parse("no separator\nmap.tmx: 3 three 4\n");
+
+        assert_eq!(collection.levels().count(), 1);
+        assert_eq!(collection.count("map.tmx"), 2);
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let mut collection = Collection::default();
+        collection.add("map.tmx", 15);
+        collection.add("map.tmx", 3);
+        collection.add("b.tmx", 7);
+
+        let text = collection.serialize();
+        assert_eq!(text, "b.tmx: 7\nmap.tmx: 3 15\n");
+
+        let parsed = Collection::parse(&text);
+        assert_eq!(parsed.levels, collection.levels);
+    }
+}
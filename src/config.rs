@@ -1,8 +1,23 @@
 use bevy::prelude::*;
 use bevy::window::{WindowPlugin, WindowResolution};
+use bevy_rapier2d::plugin::RapierConfiguration;
+
+use crate::camera::CameraMode;
+
+/// Argument de la ligne de commande qui active le rendu sous-pixel de la caméra
+const SUBPIXEL_ARG: &str = "--subpixel";
 
 pub struct ConfigPlugin;
 
+/// État global du gameplay, Frozen met en pause la physique et les inputs du joueur (transition
+/// entre deux salles par exemple) sans arrêter le rendu
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    Playing,
+    Frozen,
+}
+
 impl Plugin for ConfigPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, set_window_icon)
@@ -24,7 +39,29 @@ impl Plugin for ConfigPlugin {
                     }),
             )
             .insert_resource(Msaa::Off)
-            .insert_resource(ClearColor(Color::rgb(0.2, 0.2, 0.3)));
+            .insert_resource(camera_mode())
+            .insert_resource(ClearColor(Color::rgb(0.2, 0.2, 0.3)))
+            .init_state::<GameState>()
+            .add_systems(OnEnter(GameState::Frozen), pause_physics)
+            .add_systems(OnExit(GameState::Frozen), resume_physics);
     }
 }
 fn set_window_icon() {}
+
+/// Mode de rendu de la caméra, choisi au lancement avec `--subpixel`
+fn camera_mode() -> CameraMode {
+    if std::env::args().any(|arg| arg == SUBPIXEL_ARG) {
+        CameraMode::Subpixel
+    } else {
+        CameraMode::default()
+    }
+}
+
+fn pause_physics(mut config: ResMut<RapierConfiguration>) {
+    config.physics_pipeline_active = false;
+}
+
+fn resume_physics(mut config: ResMut<RapierConfiguration>) {
+    config.physics_pipeline_active = true;
+}
+
@@ -11,16 +11,24 @@ use bevy_spritesheet_animation::prelude::*;
 mod camera;
 mod player;
 mod world;
+mod collectible;
 mod config;
 mod editor;
+mod mechanics;
+mod particles;
+mod room;
 mod tile;
 
 // on spécifie ce qu'on importe dans le namespace
 use camera::CameraPlugin;
 use player::PlayerPlugin;
 use world::WorldPlugin;
+use collectible::CollectiblePlugin;
 use config::ConfigPlugin;
 use editor::PlsEditorPlugin;
+use mechanics::{MechanicsPlugin, OneWayPlatformHooks};
+use particles::ParticlesPlugin;
+use room::RoomPlugin;
 use tile::TileWorldPlugin;
 
 // les #[bonjour] sont des macros, celle-ci précise le point d'entrée du programme a bevy
@@ -31,8 +39,8 @@ fn main() {
         .add_plugins((
             // default plugins est nécessaire pour avoir des trucs de bases de bevy
             ConfigPlugin,
-            // physics engine
-            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0),
+            // physics engine, les hooks servent aux plateformes a sens unique
+            RapierPhysicsPlugin::<OneWayPlatformHooks>::pixels_per_meter(100.0),
             RapierDebugRenderPlugin::default(),
             // animations
             SpritesheetAnimationPlugin,
@@ -40,7 +48,11 @@ fn main() {
             CameraPlugin,
             WorldPlugin,
             TileWorldPlugin,
+            RoomPlugin,
+            MechanicsPlugin,
+            CollectiblePlugin,
             PlayerPlugin,
+            ParticlesPlugin,
             PlsEditorPlugin,
         ))
         .run();
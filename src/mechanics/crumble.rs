@@ -0,0 +1,238 @@
+// blocs qui s'effritent : objets rectangles de classe "crumble_block", quand le joueur se tient
+// dessus le bloc tremble puis disparait (ou tombe), il réapparait quand le joueur n'est plus a
+// sa place
+//
+// propriétés reconnues :
+// - delay (float) : temps avant que le bloc disparaisse, en secondes
+// - respawn (float) : temps avant que le bloc réapparaisse, en secondes
+// - fall (bool) : le bloc tombe au lieu de disparaitre sur place, la chute n'est qu'un effet
+//   visuel : le collider est retiré dès que le bloc disparait, le bloc qui tombe ne touche ni le
+//   joueur ni le décor
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::player::{Ground, Player, PlayerDied};
+use crate::room::RoomPaused;
+use crate::tile::objects::TiledObject;
+
+const DEFAULT_DELAY: f32 = 0.5;
+const DEFAULT_RESPAWN: f32 = 2.0;
+
+/// Amplitude du tremblement en pixels
+const SHAKE_AMPLITUDE: f32 = 1.0;
+/// Durée du fondu a la disparition et a la réapparition
+const FADE_TIME: f32 = 0.2;
+/// Accélération d'un bloc qui tombe, en pixels par seconde au carré
+const FALL_GRAVITY: f32 = 600.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CrumbleState {
+    Idle,
+    Crumbling(f32),
+    Gone(f32),
+    Respawning(f32),
+}
+
+#[derive(Component)]
+pub struct CrumbleBlock {
+    pub delay: f32,
+    pub respawn: f32,
+    pub falls: bool,
+    pub state: CrumbleState,
+    size: Vec2,
+    /// Entité du collider, enfant du bloc
+    pub solid: Entity,
+    /// Le collider est actif
+    enabled: bool,
+}
+
+impl CrumbleState {
+    /// Le bloc bloque le joueur dans cet état
+    fn is_solid(&self) -> bool {
+        !matches!(self, CrumbleState::Gone(_))
+    }
+}
+
+/// Sprite du bloc, séparé du collider pour pouvoir trembler et tomber sans le déplacer
+#[derive(Component)]
+pub struct CrumbleVisual;
+
+/// Collider du bloc, séparé de l'objet pour que la mise en pause des salles (qui désactive le
+/// collider de l'objet) ne se mélange pas avec l'état du bloc
+#[derive(Component)]
+pub struct CrumbleSolid;
+
+pub fn setup_crumble_blocks(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledObject), Added<TiledObject>>,
+) {
+    for (entity, object) in &query {
+        if !object.is("crumble_block") && !object.is("crumble_blocks") {
+            continue;
+        }
+
+        let visual = commands
+            .spawn((
+                Name::new("Crumble block sprite"),
+                CrumbleVisual,
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgb(0.7, 0.55, 0.4),
+                        custom_size: Some(object.size),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ))
+            .id();
+
+        let solid = commands
+            .spawn((
+                Name::new("Crumble block collider"),
+                CrumbleSolid,
+                TransformBundle::default(),
+                RigidBody::Fixed,
+                Collider::cuboid(object.size.x / 2.0, object.size.y / 2.0),
+            ))
+            .id();
+
+        commands
+            .entity(entity)
+            .insert(CrumbleBlock {
+                delay: object.float_property("delay").unwrap_or(DEFAULT_DELAY),
+                respawn: object.float_property("respawn").unwrap_or(DEFAULT_RESPAWN),
+                falls: object.bool_property("fall").unwrap_or(false),
+                state: CrumbleState::Idle,
+                size: object.size,
+                solid,
+                enabled: true,
+            })
+            .push_children(&[visual, solid]);
+    }
+}
+
+/// Fait avancer les blocs dans leurs états
+pub fn update_crumble_blocks(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    player: Query<(Entity, &Player, Option<&Ground>)>,
+    mut blocks: Query<(&mut CrumbleBlock, &GlobalTransform)>,
+) {
+    if player.is_empty() {
+        return;
+    }
+
+    let (player_entity, player, ground) = player.single();
+    let standing_on = ground
+        .filter(|_| player.is_grounded())
+        .map(|ground| ground.entity);
+    let delta = time.delta_seconds();
+
+    for (mut block, transform) in &mut blocks {
+        block.state = match block.state {
+            CrumbleState::Idle if standing_on == Some(block.solid) => CrumbleState::Crumbling(0.0),
+            CrumbleState::Crumbling(elapsed) if elapsed + delta >= block.delay => {
+                CrumbleState::Gone(0.0)
+            }
+            CrumbleState::Crumbling(elapsed) => CrumbleState::Crumbling(elapsed + delta),
+            CrumbleState::Gone(elapsed) if elapsed + delta >= block.respawn => {
+                // on attend que le joueur ne soit plus a la place du bloc
+                let only_player = |entity: Entity| entity == player_entity;
+                let overlapping = rapier_context
+                    .intersection_with_shape(
+                        transform.translation().truncate(),
+                        0.0,
+                        &Collider::cuboid(block.size.x / 2.0, block.size.y / 2.0),
+                        QueryFilter::default().predicate(&only_player),
+                    )
+                    .is_some();
+
+                if overlapping {
+                    CrumbleState::Gone(elapsed + delta)
+                } else {
+                    CrumbleState::Respawning(0.0)
+                }
+            }
+            CrumbleState::Gone(elapsed) => CrumbleState::Gone(elapsed + delta),
+            CrumbleState::Respawning(elapsed) if elapsed + delta >= FADE_TIME => CrumbleState::Idle,
+            CrumbleState::Respawning(elapsed) => CrumbleState::Respawning(elapsed + delta),
+            CrumbleState::Idle => CrumbleState::Idle,
+        };
+    }
+}
+
+/// Tremblement, chute et fondu du sprite selon l'état du bloc
+pub fn update_crumble_visuals(
+    time: Res<Time>,
+    blocks: Query<&CrumbleBlock>,
+    mut visuals: Query<(&Parent, &mut Transform, &mut Sprite), With<CrumbleVisual>>,
+) {
+    for (parent, mut transform, mut sprite) in &mut visuals {
+        let Ok(block) = blocks.get(parent.get()) else {
+            continue;
+        };
+
+        let (offset, alpha) = match block.state {
+            CrumbleState::Idle => (Vec2::ZERO, 1.0),
+            CrumbleState::Crumbling(elapsed) => {
+                // tremble de plus en plus fort
+                let strength = (elapsed / block.delay).min(1.0) * SHAKE_AMPLITUDE;
+                let t = time.elapsed_seconds() * 60.0;
+                (Vec2::new(t.sin(), (t * 1.3).cos()).round() * strength, 1.0)
+            }
+            CrumbleState::Gone(elapsed) => {
+                // seul le sprite tombe, le collider est déjà désactivé
+                let fall = if block.falls {
+                    0.5 * FALL_GRAVITY * elapsed * elapsed
+                } else {
+                    0.0
+                };
+                let alpha = 1.0 - (elapsed / FADE_TIME).min(1.0);
+                (Vec2::new(0.0, -fall), alpha)
+            }
+            CrumbleState::Respawning(elapsed) => (Vec2::ZERO, (elapsed / FADE_TIME).min(1.0)),
+        };
+
+        transform.translation.x = offset.x;
+        transform.translation.y = offset.y;
+        sprite.color.set_a(alpha);
+    }
+}
+
+/// Tous les blocs reviennent quand le joueur meurt
+pub fn reset_crumble_blocks(
+    mut deaths: EventReader<PlayerDied>,
+    mut blocks: Query<&mut CrumbleBlock>,
+) {
+    if deaths.read().last().is_none() {
+        return;
+    }
+
+    for mut block in &mut blocks {
+        if block.state != CrumbleState::Idle {
+            block.state = CrumbleState::Idle;
+        }
+    }
+}
+
+/// Active le collider des blocs solides dont la salle n'est pas en pause
+pub fn sync_crumble_colliders(
+    mut commands: Commands,
+    mut blocks: Query<(&mut CrumbleBlock, Has<RoomPaused>)>,
+) {
+    for (mut block, paused) in &mut blocks {
+        let enabled = block.state.is_solid() && !paused;
+
+        if block.enabled == enabled {
+            continue;
+        }
+        block.enabled = enabled;
+
+        if enabled {
+            commands.entity(block.solid).remove::<ColliderDisabled>();
+        } else {
+            commands.entity(block.solid).insert(ColliderDisabled);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// portes et blocs activables : objets rectangles de classe "gate", solides et visibles tant
+// qu'ils sont actifs, les déclencheurs les ouvrent (deactivate) ou les font apparaître (activate)
+//
+// propriétés reconnues :
+// - active (bool) : la porte est fermée au chargement, vrai par défaut
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::mechanics::Active;
+use crate::player::Player;
+use crate::tile::objects::{object_rect, TiledObject};
+
+/// Marge autour du joueur pour ne pas faire apparaître un bloc juste contre lui
+const PLAYER_MARGIN: Vec2 = Vec2::new(6.0, 10.0);
+
+#[derive(Component)]
+pub struct Gate {
+    /// La porte bloque le passage, peut être en retard sur Active tant que le joueur est dedans
+    pub solid: bool,
+}
+
+/// Collider et sprite de la porte, séparés de l'objet pour que la mise en pause des salles ne
+/// réactive pas une porte ouverte
+#[derive(Component)]
+pub struct GateSolid;
+
+pub fn setup_gates(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledObject), Added<TiledObject>>,
+) {
+    for (entity, object) in &query {
+        if !object.is("gate") && !object.is("gates") {
+            continue;
+        }
+
+        let active = object.bool_property("active").unwrap_or(true);
+
+        let mut solid = commands.spawn((
+            Name::new("Gate solid"),
+            GateSolid,
+            RigidBody::Fixed,
+            Collider::cuboid(object.size.x / 2.0, object.size.y / 2.0),
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgb(0.5, 0.5, 0.65),
+                    custom_size: Some(object.size),
+                    ..Default::default()
+                },
+                visibility: if active {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                },
+                ..Default::default()
+            },
+        ));
+        if !active {
+            solid.insert(ColliderDisabled);
+        }
+        let solid = solid.id();
+
+        commands
+            .entity(entity)
+            .insert((Gate { solid: active }, Active(active)))
+            .add_child(solid);
+    }
+}
+
+/// Ouvre ou ferme les portes selon Active, une porte attend que le joueur soit sorti pour se
+/// fermer
+pub fn update_gates(
+    mut commands: Commands,
+    player: Query<&Transform, With<Player>>,
+    mut gates: Query<(
+        &mut Gate,
+        &Active,
+        &TiledObject,
+        &GlobalTransform,
+        &Children,
+    )>,
+    mut solids: Query<&mut Visibility, With<GateSolid>>,
+) {
+    let player_position = player
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+
+    for (mut gate, active, object, transform, children) in &mut gates {
+        if gate.solid == active.0 {
+            continue;
+        }
+
+        if active.0 {
+            let rect = object_rect(object, transform);
+            let blocked = player_position.is_some_and(|position| {
+                rect.intersect(Rect::from_center_size(position, PLAYER_MARGIN * 2.0))
+                    .size()
+                    .cmpgt(Vec2::ZERO)
+                    .all()
+            });
+            if blocked {
+                continue;
+            }
+        }
+
+        gate.solid = active.0;
+
+        for &child in children {
+            let Ok(mut visibility) = solids.get_mut(child) else {
+                continue;
+            };
+
+            if active.0 {
+                *visibility = Visibility::Inherited;
+                commands.entity(child).remove::<ColliderDisabled>();
+            } else {
+                *visibility = Visibility::Hidden;
+                commands.entity(child).insert(ColliderDisabled);
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+// mécaniques de gameplay posées dans les niveaux (plateformes, ressorts...), la plupart sont
+// créées a partir des objets Tiled (voir tile/objects.rs)
+
+use bevy::prelude::*;
+
+use crate::config::GameState;
+
+pub mod crumble;
+pub mod gate;
+pub mod moving;
+pub mod one_way;
+pub mod spike;
+pub mod spring;
+pub mod switch;
+pub mod trigger;
+pub mod wind;
+
+pub use moving::MovingPlatform;
+pub use one_way::{DropThrough, OneWayPlatform, OneWayPlatformHooks};
+pub use spring::LaunchPlayer;
+pub use trigger::TriggerEvent;
+pub use wind::PlayerWind;
+
+pub struct MechanicsPlugin;
+
+/// Une mécanique inactive ne fait plus rien, les triggers peuvent l'activer ou la désactiver
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Active(pub bool);
+
+impl Plugin for MechanicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LaunchPlayer>()
+            .add_event::<TriggerEvent>()
+            .add_event::<trigger::RunScript>()
+            .register_type::<spike::Spike>()
+            .init_resource::<PlayerWind>()
+            .init_resource::<trigger::TriggerFlags>()
+            .init_resource::<switch::SwitchColors>()
+            .add_systems(
+                Update,
+                (
+                    one_way::setup_one_way_platforms,
+                    one_way::setup_one_way_tiles,
+                    one_way::update_drop_through,
+                    moving::setup_moving_platforms,
+                    crumble::setup_crumble_blocks,
+                    spring::setup_springs,
+                    spike::setup_spikes,
+                    spring::trigger_springs.run_if(in_state(GameState::Playing)),
+                    wind::setup_wind_zones,
+                    (wind::update_player_wind, wind::update_wind_particles),
+                    (gate::setup_gates, gate::update_gates).chain(),
+                    (
+                        switch::setup_switches,
+                        switch::setup_switch_layers,
+                        switch::collect_switch_colors,
+                        switch::hit_switches.run_if(in_state(GameState::Playing)),
+                        switch::update_switch_layers,
+                    )
+                        .chain(),
+                    (
+                        trigger::setup_triggers,
+                        trigger::reset_flags,
+                        trigger::update_triggers.run_if(in_state(GameState::Playing)),
+                        trigger::run_scripts,
+                        trigger::listen_events,
+                    )
+                        .chain(),
+                    (
+                        crumble::reset_crumble_blocks,
+                        crumble::update_crumble_blocks.run_if(in_state(GameState::Playing)),
+                        crumble::update_crumble_visuals,
+                        crumble::sync_crumble_colliders,
+                    )
+                        .chain(),
+                    (moving::move_platforms, moving::detect_crush)
+                        .run_if(in_state(GameState::Playing)),
+                ),
+            );
+    }
+}
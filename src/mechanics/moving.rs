@@ -0,0 +1,291 @@
+// plateformes mobiles : objets polyline ou polygon de classe "moving_platform", la plateforme suit la ligne
+// en partant de son premier point
+//
+// propriétés reconnues :
+// - width, height (float) : taille de la plateforme, 32x8 par défaut
+// - speed (float) : vitesse en pixels par seconde
+// - easing (string) : "linear" ou "smooth" (ralentit a chaque point de la ligne)
+// - mode (string) : "ping_pong" (aller-retour), "loop" (revient au départ) ou "once"
+//
+// un joueur écrasé entre une plateforme et un mur meurt
+
+use bevy::{log, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+use crate::mechanics::OneWayPlatform;
+use crate::player::{Player, PlayerDied};
+use crate::tile::objects::{TiledObject, TiledObjectShape};
+
+const DEFAULT_SIZE: Vec2 = Vec2::new(32.0, 8.0);
+const DEFAULT_SPEED: f32 = 40.0;
+
+/// Demi-taille du collider utilisé pour détecter l'écrasement, plus petit que celui du joueur
+/// pour ignorer les contacts normaux
+const CRUSH_HALF_HEIGHT: f32 = 2.0;
+const CRUSH_RADIUS: f32 = 2.0;
+
+/// Capsule du joueur (voir player/setup.rs), agrandie de TOUCH_MARGIN pour savoir ce qu'il touche
+const PLAYER_HALF_HEIGHT: f32 = 4.0;
+const PLAYER_RADIUS: f32 = 4.0;
+const TOUCH_MARGIN: f32 = 1.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PlatformEasing {
+    Linear,
+    Smooth,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PlatformMode {
+    PingPong,
+    Loop,
+    Once,
+}
+
+#[derive(Component)]
+pub struct MovingPlatform {
+    /// Points du chemin dans l'espace du parent
+    path: Vec<Vec2>,
+    pub speed: f32,
+    pub easing: PlatformEasing,
+    pub mode: PlatformMode,
+    /// Distance parcourue depuis le début
+    distance: f32,
+    velocity: Vec2,
+}
+
+impl MovingPlatform {
+    fn from_object(object: &TiledObject, origin: Vec2) -> Option<MovingPlatform> {
+        let (points, closed) = match &object.shape {
+            TiledObjectShape::Polyline(points) => (points, false),
+            TiledObjectShape::Polygon(points) => (points, true),
+            _ => return None,
+        };
+
+        let mut path: Vec<Vec2> = points.iter().map(|point| origin + *point).collect();
+
+        let mode = match object.string_property("mode").unwrap_or_default() {
+            mode if mode.eq_ignore_ascii_case("loop") => PlatformMode::Loop,
+            mode if mode.eq_ignore_ascii_case("once") => PlatformMode::Once,
+            _ if closed => PlatformMode::Loop,
+            _ => PlatformMode::PingPong,
+        };
+
+        // la boucle revient au premier point
+        if mode == PlatformMode::Loop {
+            path.push(*path.first()?);
+        }
+
+        let easing = match object.string_property("easing") {
+            Some(easing) if easing.eq_ignore_ascii_case("smooth") => PlatformEasing::Smooth,
+            _ => PlatformEasing::Linear,
+        };
+
+        Some(MovingPlatform {
+            path,
+            speed: object.float_property("speed").unwrap_or(DEFAULT_SPEED),
+            easing,
+            mode,
+            distance: 0.0,
+            velocity: Vec2::ZERO,
+        })
+    }
+
+    /// Vitesse de la plateforme pendant cette frame
+    pub fn velocity(&self) -> Vec2 {
+        self.velocity
+    }
+
+    fn length(&self) -> f32 {
+        self.path
+            .windows(2)
+            .map(|segment| segment[0].distance(segment[1]))
+            .sum()
+    }
+
+    /// Position sur le chemin après avoir parcouru une certaine distance
+    fn position_at(&self, distance: f32) -> Vec2 {
+        let length = self.length();
+
+        if length <= 0.0 {
+            return self.path[0];
+        }
+
+        let mut distance = match self.mode {
+            PlatformMode::PingPong => length - ((distance % (length * 2.0)) - length).abs(),
+            PlatformMode::Loop => distance % length,
+            PlatformMode::Once => distance.min(length),
+        };
+
+        for segment in self.path.windows(2) {
+            let segment_length = segment[0].distance(segment[1]);
+
+            if distance <= segment_length && segment_length > 0.0 {
+                let t = distance / segment_length;
+                let t = match self.easing {
+                    PlatformEasing::Linear => t,
+                    PlatformEasing::Smooth => t * t * (3.0 - 2.0 * t),
+                };
+                return segment[0].lerp(segment[1], t);
+            }
+
+            distance -= segment_length;
+        }
+
+        self.path[self.path.len() - 1]
+    }
+}
+
+pub fn setup_moving_platforms(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledObject, &Transform), Added<TiledObject>>,
+) {
+    for (entity, object, transform) in &query {
+        if !object.is("moving_platform") && !object.is("moving_platforms") {
+            continue;
+        }
+
+        let Some(platform) = MovingPlatform::from_object(object, transform.translation.truncate())
+        else {
+            log::warn!(
+                "Moving platform {} must be a polyline or polygon",
+                object.id
+            );
+            continue;
+        };
+
+        let size = Vec2::new(
+            object.float_property("width").unwrap_or(DEFAULT_SIZE.x),
+            object.float_property("height").unwrap_or(DEFAULT_SIZE.y),
+        );
+
+        commands.entity(entity).insert((
+            platform,
+            RigidBody::KinematicVelocityBased,
+            Collider::cuboid(size.x / 2.0, size.y / 2.0),
+            Velocity::zero(),
+            Sprite {
+                color: Color::rgb(0.6, 0.6, 0.7),
+                custom_size: Some(size),
+                ..Default::default()
+            },
+            Handle::<Image>::default(),
+        ));
+    }
+}
+
+/// Avance les plateformes sur leur chemin, la vitesse est calculée pour que Rapier amène la
+/// plateforme a sa prochaine position
+pub fn move_platforms(
+    time: Res<Time>,
+    mut query: Query<(&mut MovingPlatform, &mut Velocity, &Transform), Without<RigidBodyDisabled>>,
+) {
+    let delta = time.delta_seconds();
+
+    if delta <= 0.0 {
+        return;
+    }
+
+    for (mut platform, mut velocity, transform) in &mut query {
+        platform.distance += platform.speed * delta;
+
+        let target = platform.position_at(platform.distance);
+        let linvel = (target - transform.translation.truncate()) / delta;
+
+        platform.velocity = linvel;
+        velocity.linvel = linvel;
+    }
+}
+
+/// Tue le joueur écrasé : il touche une plateforme mobile et son centre rentre dans un autre
+/// collider solide, ou une plateforme rentre dans son centre pendant qu'il touche un mur
+pub fn detect_crush(
+    rapier_context: Res<RapierContext>,
+    mut deaths: EventWriter<PlayerDied>,
+    platforms: Query<(), With<MovingPlatform>>,
+    one_way: Query<(), With<OneWayPlatform>>,
+    player: Query<(Entity, &Transform), With<Player>>,
+) {
+    if player.is_empty() || platforms.is_empty() {
+        return;
+    }
+
+    let (entity, transform) = player.single();
+    let position = transform.translation.truncate();
+
+    let overlaps = |shape: &Collider, want_platform: bool| {
+        let predicate =
+            |other: Entity| !one_way.contains(other) && platforms.contains(other) == want_platform;
+        let filter = QueryFilter::exclude_dynamic()
+            .exclude_sensors()
+            .exclude_rigid_body(entity)
+            .predicate(&predicate);
+
+        rapier_context
+            .intersection_with_shape(position, 0.0, shape, filter)
+            .is_some()
+    };
+
+    let center = Collider::capsule_y(CRUSH_HALF_HEIGHT, CRUSH_RADIUS);
+    let touch = Collider::capsule_y(PLAYER_HALF_HEIGHT, PLAYER_RADIUS + TOUCH_MARGIN);
+
+    let crushed = (overlaps(&touch, true) && overlaps(&center, false))
+        || (overlaps(&center, true) && overlaps(&touch, false));
+
+    if crushed {
+        deaths.send(PlayerDied);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn platform(mode: PlatformMode, easing: PlatformEasing) -> MovingPlatform {
+        MovingPlatform {
+            path: vec![Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(10.0, 10.0)],
+            speed: DEFAULT_SPEED,
+            easing,
+            mode,
+            distance: 0.0,
+            velocity: Vec2::ZERO,
+        }
+    }
+
+    #[test]
+    fn samples_along_segments() {
+        let platform = platform(PlatformMode::Once, PlatformEasing::Linear);
+
+        assert_eq!(platform.position_at(0.0), Vec2::ZERO);
+        assert_eq!(platform.position_at(5.0), Vec2::new(5.0, 0.0));
+        assert_eq!(platform.position_at(15.0), Vec2::new(10.0, 5.0));
+        // reste au bout du chemin
+        assert_eq!(platform.position_at(50.0), Vec2::new(10.0, 10.0));
+    }
+
+    #[test]
+    fn ping_pong_comes_back() {
+        let platform = platform(PlatformMode::PingPong, PlatformEasing::Linear);
+
+        assert_eq!(platform.position_at(20.0), Vec2::new(10.0, 10.0));
+        assert_eq!(platform.position_at(25.0), Vec2::new(10.0, 5.0));
+        assert_eq!(platform.position_at(35.0), Vec2::new(5.0, 0.0));
+        assert_eq!(platform.position_at(40.0), Vec2::ZERO);
+    }
+
+    #[test]
+    fn loop_wraps_around() {
+        let platform = platform(PlatformMode::Loop, PlatformEasing::Linear);
+
+        assert_eq!(platform.position_at(25.0), Vec2::new(5.0, 0.0));
+    }
+
+    #[test]
+    fn smooth_easing_keeps_segment_ends() {
+        let platform = platform(PlatformMode::Once, PlatformEasing::Smooth);
+
+        assert_eq!(platform.position_at(5.0), Vec2::new(5.0, 0.0));
+        assert!(platform.position_at(2.0).x < 2.0);
+        assert_eq!(platform.position_at(10.0), Vec2::new(10.0, 0.0));
+    }
+}
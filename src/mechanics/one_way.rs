@@ -0,0 +1,118 @@
+// plateformes a sens unique : on peut sauter au travers par en dessous et tenir dessus, en
+// appuyant sur bas + saut le joueur passe au travers
+//
+// les contacts sont filtrés par un hook de Rapier, les colliders concernés doivent avoir
+// ActiveHooks::MODIFY_SOLVER_CONTACTS
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use bevy_rapier2d::rapier::math::Vector;
+
+use crate::tile::objects::TiledObject;
+use crate::tile::TileColliders;
+
+/// Temps pendant lequel le joueur traverse les plateformes après avoir appuyé sur bas + saut
+pub const DROP_THROUGH_TIME: f32 = 0.25;
+
+/// Vitesse verticale a partir de laquelle un corps qui monte traverse les plateformes
+pub const RISING_THRESHOLD: f32 = 1.0;
+
+/// Angle toléré autour de la normale de la plateforme pour garder un contact
+const ALLOWED_ANGLE: f32 = 0.1;
+
+/// Plateforme traversable par en dessous (objet de classe "one_way_platform" ou tuile avec la
+/// propriété isOneWay)
+#[derive(Component)]
+pub struct OneWayPlatform;
+
+/// L'entité traverse les plateformes a sens unique tant que le timer tourne
+#[derive(Component)]
+pub struct DropThrough(pub Timer);
+
+impl Default for DropThrough {
+    fn default() -> Self {
+        DropThrough(Timer::from_seconds(DROP_THROUGH_TIME, TimerMode::Once))
+    }
+}
+
+/// Hooks de Rapier, a passer en paramètre du RapierPhysicsPlugin
+#[derive(SystemParam)]
+pub struct OneWayPlatformHooks<'w, 's> {
+    platforms: Query<'w, 's, (), With<OneWayPlatform>>,
+    bodies: Query<'w, 's, (Option<&'static Velocity>, Has<DropThrough>)>,
+}
+
+impl BevyPhysicsHooks for OneWayPlatformHooks<'_, '_> {
+    fn modify_solver_contacts(&self, context: ContactModificationContextView) {
+        let (platform, other, allowed_normal) = if self.platforms.contains(context.collider1()) {
+            (context.collider1(), context.collider2(), Vector::y())
+        } else if self.platforms.contains(context.collider2()) {
+            (context.collider2(), context.collider1(), -Vector::y())
+        } else {
+            return;
+        };
+
+        // deux plateformes ne se bloquent pas entre elles
+        if self.platforms.contains(other) || platform == other {
+            return;
+        }
+
+        if let Ok((velocity, dropping)) = self.bodies.get(other) {
+            let rising = velocity.is_some_and(|velocity| velocity.linvel.y > RISING_THRESHOLD);
+
+            if dropping || rising {
+                context.raw.solver_contacts.clear();
+                return;
+            }
+        }
+
+        // garde seulement les contacts par dessus la plateforme
+        context
+            .raw
+            .update_as_oneway_platform(&allowed_normal, ALLOWED_ANGLE);
+    }
+}
+
+pub fn setup_one_way_platforms(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledObject), Added<TiledObject>>,
+) {
+    for (entity, object) in &query {
+        if object.is("one_way_platform") || object.is("one_way_platforms") {
+            commands.entity(entity).insert((
+                OneWayPlatform,
+                RigidBody::Fixed,
+                Collider::cuboid(object.size.x / 2.0, object.size.y / 2.0),
+                ActiveHooks::MODIFY_SOLVER_CONTACTS,
+            ));
+        }
+    }
+}
+
+/// Les colliders des tuiles isOneWay sont créés par le chargement de la map, on en fait des
+/// plateformes a sens unique
+pub fn setup_one_way_tiles(
+    mut commands: Commands,
+    query: Query<(Entity, &TileColliders), Added<TileColliders>>,
+) {
+    for (entity, colliders) in &query {
+        if colliders.one_way {
+            commands
+                .entity(entity)
+                .insert((OneWayPlatform, ActiveHooks::MODIFY_SOLVER_CONTACTS));
+        }
+    }
+}
+
+pub fn update_drop_through(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut DropThrough)>,
+) {
+    for (entity, mut drop_through) in &mut query {
+        if drop_through.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<DropThrough>();
+        }
+    }
+}
@@ -0,0 +1,48 @@
+// pics : objets de classe "spike" ou du calque "spikes", le component Spike garde le côté vers
+// lequel ils pointent
+//
+// propriétés reconnues :
+// - direction (string) : "up", "down", "left" ou "right", "up" par défaut
+//
+// Spike peut aussi être donné par une propriété "Spike" (voir tile/properties.rs)
+
+use bevy::prelude::*;
+
+use crate::tile::objects::TiledObject;
+use crate::tile::properties::ReflectFromTiled;
+
+/// Côté vers lequel pointent les pics
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[reflect(Component, Default, FromTiled)]
+pub enum Spike {
+    #[default]
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+pub fn setup_spikes(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledObject), Added<TiledObject>>,
+) {
+    for (entity, object) in &query {
+        if !object.is("spike") && !object.is("spikes") {
+            continue;
+        }
+
+        let spike = match object
+            .string_property("direction")
+            .unwrap_or("up")
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "down" => Spike::Down,
+            "left" => Spike::Left,
+            "right" => Spike::Right,
+            _ => Spike::Up,
+        };
+
+        commands.entity(entity).insert(spike);
+    }
+}
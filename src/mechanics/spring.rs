@@ -0,0 +1,150 @@
+// ressorts et bumpers qui propulsent le joueur
+//
+// ressorts : objets de classe "spring", propriétés :
+// - direction (string) : "up", "down", "left" ou "right" comme pour les pics, "up" par défaut
+// - force (float) : vitesse donnée au joueur
+//
+// bumpers : objets de classe "bumper", ils repoussent le joueur depuis leur centre, propriété :
+// - force (float) : vitesse donnée au joueur
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::player::Player;
+use crate::tile::objects::{TiledObject, TiledObjectShape};
+
+const DEFAULT_SPRING_FORCE: f32 = 300.0;
+const DEFAULT_BUMPER_FORCE: f32 = 250.0;
+
+/// Demande au joueur d'être propulsé, le saut et le dash en cours sont annulés
+#[derive(Event, Clone, Copy)]
+pub struct LaunchPlayer {
+    pub velocity: Vec2,
+}
+
+#[derive(Component)]
+pub struct Spring {
+    pub direction: Vec2,
+    pub force: f32,
+}
+
+#[derive(Component)]
+pub struct Bumper {
+    pub force: f32,
+}
+
+fn parse_direction(direction: &str) -> Vec2 {
+    match direction.to_ascii_lowercase().as_str() {
+        "down" => Vec2::NEG_Y,
+        "left" => Vec2::NEG_X,
+        "right" => Vec2::X,
+        _ => Vec2::Y,
+    }
+}
+
+pub fn setup_springs(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledObject), Added<TiledObject>>,
+) {
+    for (entity, object) in &query {
+        let (color, collider) = if object.is("spring") || object.is("springs") {
+            commands.entity(entity).insert(Spring {
+                direction: parse_direction(object.string_property("direction").unwrap_or("up")),
+                force: object
+                    .float_property("force")
+                    .unwrap_or(DEFAULT_SPRING_FORCE),
+            });
+
+            (
+                Color::rgb(0.9, 0.8, 0.2),
+                Collider::cuboid(object.size.x / 2.0, object.size.y / 2.0),
+            )
+        } else if object.is("bumper") || object.is("bumpers") {
+            commands.entity(entity).insert(Bumper {
+                force: object
+                    .float_property("force")
+                    .unwrap_or(DEFAULT_BUMPER_FORCE),
+            });
+
+            let collider = match object.shape {
+                TiledObjectShape::Ellipse => Collider::ball(object.size.min_element() / 2.0),
+                _ => Collider::cuboid(object.size.x / 2.0, object.size.y / 2.0),
+            };
+            (Color::rgb(0.9, 0.3, 0.5), collider)
+        } else {
+            continue;
+        };
+
+        commands.entity(entity).insert((
+            collider,
+            Sensor,
+            ActiveEvents::COLLISION_EVENTS,
+            Sprite {
+                color,
+                custom_size: Some(object.size),
+                ..Default::default()
+            },
+            Handle::<Image>::default(),
+        ));
+    }
+}
+
+/// Propulse le joueur quand il touche un ressort ou un bumper
+pub fn trigger_springs(
+    mut collisions: EventReader<CollisionEvent>,
+    mut launches: EventWriter<LaunchPlayer>,
+    player: Query<&GlobalTransform, With<Player>>,
+    springs: Query<&Spring>,
+    bumpers: Query<(&Bumper, &GlobalTransform)>,
+) {
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = collision else {
+            continue;
+        };
+
+        let (player_transform, other) = match (player.get(*first), player.get(*second)) {
+            (Ok(transform), _) => (transform, *second),
+            (_, Ok(transform)) => (transform, *first),
+            _ => continue,
+        };
+
+        if let Ok(spring) = springs.get(other) {
+            launches.send(LaunchPlayer {
+                velocity: spring.direction * spring.force,
+            });
+        } else if let Ok((bumper, transform)) = bumpers.get(other) {
+            let direction = (player_transform.translation() - transform.translation())
+                .truncate()
+                .normalize_or_zero();
+            // joueur pile au centre, on le pousse vers le haut
+            let direction = if direction == Vec2::ZERO {
+                Vec2::Y
+            } else {
+                direction
+            };
+
+            launches.send(LaunchPlayer {
+                velocity: direction * bumper.force,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_directions() {
+        assert_eq!(parse_direction("up"), Vec2::Y);
+        assert_eq!(parse_direction("down"), Vec2::NEG_Y);
+        assert_eq!(parse_direction("Left"), Vec2::NEG_X);
+        assert_eq!(parse_direction("RIGHT"), Vec2::X);
+    }
+
+    #[test]
+    fn unknown_direction_is_up() {
+        assert_eq!(parse_direction(""), Vec2::Y);
+        assert_eq!(parse_direction("sideways"), Vec2::Y);
+    }
+}
@@ -0,0 +1,344 @@
+// blocs a interrupteur : des calques de tuiles de couleurs différentes, seuls ceux de la couleur
+// active sont solides et opaques, toucher un interrupteur change de couleur
+//
+// calques de tuiles, propriété :
+// - switch (string) : couleur du calque, la première couleur dans l'ordre des calques est active
+//   au chargement
+//
+// interrupteurs : objets rectangles de classe "switch", propriétés :
+// - color (string) : couleur activée, sinon l'interrupteur passe a la couleur suivante
+// - dash (bool) : l'interrupteur ne réagit qu'au dash
+//
+// chaque changement lance l'événement "switch_<couleur>" pour les écouteurs (voir trigger.rs)
+
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::camera::CameraShakeEvent;
+use crate::mechanics::TriggerEvent;
+use crate::player::{Dash, Player};
+use crate::tile::objects::{object_rect, TiledObject};
+use crate::tile::{LevelUnloaded, TiledLayer};
+
+/// Opacité des blocs de couleur inactive, multipliée par celle du calque
+const INACTIVE_ALPHA: f32 = 0.25;
+
+/// Demi taille de la hitbox du joueur (capsule de 8 par 16)
+const PLAYER_HALF_SIZE: Vec2 = Vec2::new(4.0, 8.0);
+
+/// Marge retirée a la hitbox pour qu'un joueur collé contre un bloc ne compte pas comme dedans
+const INSIDE_INSET: f32 = 1.0;
+
+/// Propriété de calque donnant la couleur du calque
+const SWITCH_PROPERTY: &str = "switch";
+
+/// Calque de tuiles qui n'est solide que quand sa couleur est active
+#[derive(Component)]
+pub struct SwitchLayer {
+    pub color: String,
+    /// Index du calque dans la map, donne l'ordre des couleurs
+    pub order: u32,
+    /// Opacité du calque dans Tiled
+    pub opacity: f32,
+    solid: bool,
+}
+
+/// Couleurs du niveau dans l'ordre des calques, et couleur active
+#[derive(Resource, Default)]
+pub struct SwitchColors {
+    pub colors: Vec<String>,
+    pub active: Option<String>,
+}
+
+impl SwitchColors {
+    fn is_active(&self, color: &str) -> bool {
+        self.active.as_ref().map_or(true, |active| active == color)
+    }
+
+    fn next(&self) -> Option<String> {
+        let index = self
+            .colors
+            .iter()
+            .position(|color| Some(color) == self.active.as_ref())
+            .map_or(0, |index| (index + 1) % self.colors.len());
+
+        self.colors.get(index).cloned()
+    }
+}
+
+#[derive(Component)]
+pub struct Switch {
+    pub color: Option<String>,
+    pub dash_only: bool,
+    /// Le joueur touchait l'interrupteur a la frame précédente
+    touching: bool,
+}
+
+pub fn setup_switches(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledObject), Added<TiledObject>>,
+) {
+    for (entity, object) in &query {
+        if !object.is("switch") && !object.is("switches") {
+            continue;
+        }
+
+        commands.entity(entity).insert((
+            Switch {
+                color: object
+                    .string_property("color")
+                    .filter(|color| !color.is_empty())
+                    .map(str::to_string),
+                dash_only: object.bool_property("dash").unwrap_or(false),
+                touching: false,
+            },
+            Sprite {
+                color: Color::rgb(0.8, 0.8, 0.9),
+                custom_size: Some(object.size),
+                ..Default::default()
+            },
+            Handle::<Image>::default(),
+        ));
+    }
+}
+
+pub fn setup_switch_layers(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledLayer), Added<TiledLayer>>,
+) {
+    for (entity, layer) in &query {
+        let Some(tiled::PropertyValue::StringValue(color)) = layer.properties.get(SWITCH_PROPERTY)
+        else {
+            continue;
+        };
+
+        commands.entity(entity).insert(SwitchLayer {
+            color: color.clone(),
+            order: layer.index,
+            opacity: layer.opacity,
+            solid: true,
+        });
+    }
+}
+
+/// Retrouve les couleurs quand des calques sont ajoutés, la première devient active si la
+/// couleur actuelle n'existe pas dans le niveau
+pub fn collect_switch_colors(
+    mut colors: ResMut<SwitchColors>,
+    mut unloaded: EventReader<LevelUnloaded>,
+    added: Query<(), Added<SwitchLayer>>,
+    layers: Query<&SwitchLayer>,
+) {
+    if unloaded.read().count() > 0 {
+        *colors = SwitchColors::default();
+    }
+
+    if added.is_empty() {
+        return;
+    }
+
+    let mut sorted: Vec<&SwitchLayer> = layers.iter().collect();
+    sorted.sort_by_key(|layer| layer.order);
+
+    colors.colors.clear();
+    for layer in sorted {
+        if !colors.colors.contains(&layer.color) {
+            colors.colors.push(layer.color.clone());
+        }
+    }
+
+    if !colors
+        .active
+        .as_ref()
+        .is_some_and(|active| colors.colors.contains(active))
+    {
+        colors.active = colors.colors.first().cloned();
+    }
+}
+
+/// Change la couleur active quand le joueur touche un interrupteur (ou fonce dedans)
+pub fn hit_switches(
+    mut colors: ResMut<SwitchColors>,
+    mut events: EventWriter<TriggerEvent>,
+    mut shakes: EventWriter<CameraShakeEvent>,
+    player: Query<(&Transform, Has<Dash>), With<Player>>,
+    mut switches: Query<(&mut Switch, &TiledObject, &GlobalTransform)>,
+) {
+    if player.is_empty() {
+        return;
+    }
+
+    let (player_transform, dashing) = player.single();
+    let player_rect =
+        Rect::from_center_half_size(player_transform.translation.truncate(), PLAYER_HALF_SIZE);
+
+    for (mut switch, object, transform) in &mut switches {
+        let touching = !object_rect(object, transform)
+            .intersect(player_rect)
+            .is_empty();
+        let hit = touching && !switch.touching && (dashing || !switch.dash_only);
+
+        // un interrupteur "dash" touché sans dash peut encore être activé par un dash
+        if !touching || hit || !switch.dash_only {
+            switch.touching = touching;
+        }
+
+        if !hit {
+            continue;
+        }
+
+        let Some(color) = switch.color.clone().or_else(|| colors.next()) else {
+            continue;
+        };
+
+        if colors.active.as_ref() != Some(&color) {
+            colors.active = Some(color.clone());
+            shakes.send(CameraShakeEvent::new(0.2, 0.15));
+            events.send(TriggerEvent {
+                name: format!("switch_{color}"),
+            });
+        }
+    }
+}
+
+/// Met a jour les colliders et l'opacité des calques, un calque qui devient solide attend que
+/// le joueur ne soit plus dans une de ses tuiles et reste transparent jusque la
+#[allow(clippy::type_complexity)]
+pub fn update_switch_layers(
+    mut commands: Commands,
+    colors: Res<SwitchColors>,
+    player: Query<&Transform, With<Player>>,
+    mut layers: Query<(
+        &mut SwitchLayer,
+        &TileStorage,
+        &TilemapSize,
+        &TilemapGridSize,
+        &GlobalTransform,
+        Option<&Children>,
+    )>,
+    colliders: Query<(), With<Collider>>,
+    mut tiles: Query<&mut TileColor>,
+) {
+    let player_rect = player.get_single().ok().map(|transform| {
+        Rect::from_center_half_size(
+            transform.translation.truncate(),
+            PLAYER_HALF_SIZE - INSIDE_INSET,
+        )
+    });
+
+    for (mut layer, storage, size, grid_size, transform, children) in &mut layers {
+        let active = colors.is_active(&layer.color);
+
+        if layer.solid == active {
+            continue;
+        }
+
+        if active {
+            let inverse = transform.affine().inverse();
+            let inside = player_rect.is_some_and(|rect| {
+                let min = inverse.transform_point3(rect.min.extend(0.0)).truncate();
+                let max = inverse.transform_point3(rect.max.extend(0.0)).truncate();
+                overlaps_tiles(storage, size, grid_size, Rect::from_corners(min, max))
+            });
+
+            if inside {
+                continue;
+            }
+        }
+
+        layer.solid = active;
+
+        let alpha = if active {
+            layer.opacity
+        } else {
+            layer.opacity * INACTIVE_ALPHA
+        };
+        for tile in storage.iter().flatten() {
+            if let Ok(mut color) = tiles.get_mut(*tile) {
+                color.0.set_a(alpha);
+            }
+        }
+
+        for &child in children.into_iter().flatten() {
+            if !colliders.contains(child) {
+                continue;
+            }
+
+            if active {
+                commands.entity(child).remove::<ColliderDisabled>();
+            } else {
+                commands.entity(child).insert(ColliderDisabled);
+            }
+        }
+    }
+}
+
+/// Vrai si une tuile du calque recouvre le rectangle, donné dans le repère du calque (map
+/// orthogonale, le centre de la tuile (0, 0) est a l'origine)
+fn overlaps_tiles(
+    storage: &TileStorage,
+    size: &TilemapSize,
+    grid_size: &TilemapGridSize,
+    rect: Rect,
+) -> bool {
+    let grid = Vec2::new(grid_size.x, grid_size.y);
+    let first = ((rect.min + grid / 2.0) / grid).floor();
+    let last = ((rect.max + grid / 2.0) / grid).ceil() - 1.0;
+
+    let min_x = first.x.max(0.0) as u32;
+    let min_y = first.y.max(0.0) as u32;
+    let max_x = last.x.min(size.x as f32 - 1.0);
+    let max_y = last.y.min(size.y as f32 - 1.0);
+
+    if max_x < 0.0 || max_y < 0.0 {
+        return false;
+    }
+
+    (min_x..=max_x as u32)
+        .flat_map(|x| (min_y..=max_y as u32).map(move |y| TilePos { x, y }))
+        .any(|tile_pos| storage.get(&tile_pos).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn colors(active: Option<&str>) -> SwitchColors {
+        SwitchColors {
+            colors: vec!["red".to_string(), "blue".to_string(), "green".to_string()],
+            active: active.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn next_cycles_colors() {
+        assert_eq!(colors(Some("red")).next().as_deref(), Some("blue"));
+        assert_eq!(colors(Some("green")).next().as_deref(), Some("red"));
+    }
+
+    #[test]
+    fn next_without_active_color() {
+        assert_eq!(colors(None).next().as_deref(), Some("red"));
+        assert_eq!(colors(Some("pink")).next().as_deref(), Some("red"));
+        assert_eq!(SwitchColors::default().next(), None);
+    }
+
+    #[test]
+    fn flush_player_is_not_inside() {
+        let size = TilemapSize { x: 4, y: 4 };
+        let grid_size = TilemapGridSize { x: 8.0, y: 8.0 };
+        let mut storage = TileStorage::empty(size);
+        storage.set(&TilePos { x: 2, y: 1 }, Entity::from_raw(1));
+
+        // la tuile (2, 1) couvre x de 12 a 20 et y de 4 a 12
+        let beside = Rect::new(4.0 + INSIDE_INSET, 0.0, 12.0 - INSIDE_INSET, 16.0);
+        assert!(!overlaps_tiles(&storage, &size, &grid_size, beside));
+
+        let overlapping = Rect::new(6.0, 0.0, 14.0, 16.0);
+        assert!(overlaps_tiles(&storage, &size, &grid_size, overlapping));
+
+        let outside = Rect::new(-40.0, -40.0, -30.0, -30.0);
+        assert!(!overlaps_tiles(&storage, &size, &grid_size, outside));
+    }
+}
@@ -0,0 +1,440 @@
+// déclencheurs et petite logique de niveau, tout se règle depuis Tiled
+//
+// déclencheurs : objets rectangles de classe "trigger", ils se déclenchent quand le joueur entre,
+// sort ou reste dedans, propriétés :
+// - on (string) : "enter" (par défaut), "exit" ou "stay"
+// - interval (float) : pour "stay", temps entre deux déclenchements, 1 seconde par défaut
+// - once (bool) : le déclencheur ne sert qu'une fois, vrai par défaut
+// - active (bool) : un déclencheur inactif ne fait rien tant qu'il n'est pas activé
+//
+// écouteurs : objets de classe "listener", ils se déclenchent quand un événement nommé est lancé,
+// propriété :
+// - listen (string) : nom de l'événement écouté
+//
+// propriétés communes aux déclencheurs et aux écouteurs :
+// - condition (string) : "flag:nom" (l'événement a déjà été lancé dans le niveau), "!flag:nom",
+//   "collected:3" (fraises ramassées depuis le chargement du niveau) ou "all_collected" (toutes
+//   les fraises du niveau, faux s'il n'y en a pas)
+// - event (string) : nom de l'événement lancé
+// - action (string) : "toggle", "activate", "deactivate" (component Active de l'objet target),
+//   "sound" (joue le fichier value), "checkpoint" (sur l'objet target, ou a défaut sur le
+//   déclencheur) ou "load_level" (charge la map value)
+// - target (object) : objet visé par l'action
+// - value (string) : paramètre de l'action
+//
+// les fraises lancent aussi "collected" a chaque fraise ramassée et "all_collected" quand la
+// dernière fraise du niveau l'est, un écouteur peut les attendre
+
+use std::collections::HashSet;
+
+use bevy::{log, prelude::*};
+
+use crate::collectible::{Collectible, CollectibleState};
+use crate::mechanics::Active;
+use crate::player::Player;
+use crate::room::Checkpoint;
+use crate::tile::objects::{object_rect, TiledObject};
+use crate::tile::{LevelManager, LevelUnloaded};
+
+const DEFAULT_STAY_INTERVAL: f32 = 1.0;
+
+/// Événement nommé lancé par un déclencheur ou un écouteur
+#[derive(Event, Clone, Debug)]
+pub struct TriggerEvent {
+    pub name: String,
+}
+
+/// Demande d'exécuter le script d'une entité
+#[derive(Event, Clone, Copy)]
+pub struct RunScript {
+    pub entity: Entity,
+}
+
+/// Événements déjà lancés dans le niveau actuel
+#[derive(Resource, Default)]
+pub struct TriggerFlags(pub HashSet<String>);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TriggerOn {
+    Enter,
+    Exit,
+    Stay,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Condition {
+    Flag(String),
+    NotFlag(String),
+    Collected(usize),
+    AllCollected,
+}
+
+impl Condition {
+    fn parse(condition: &str) -> Option<Condition> {
+        let condition = condition.trim();
+
+        if condition.eq_ignore_ascii_case("all_collected") {
+            return Some(Condition::AllCollected);
+        }
+
+        let (kind, value) = condition.split_once(':')?;
+        let value = value.trim();
+
+        match kind.trim().to_ascii_lowercase().as_str() {
+            "flag" => Some(Condition::Flag(value.to_string())),
+            "!flag" => Some(Condition::NotFlag(value.to_string())),
+            "collected" => value.parse().ok().map(Condition::Collected),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Action {
+    Toggle(u32),
+    Activate(u32),
+    Deactivate(u32),
+    PlaySound(String),
+    SetCheckpoint(Option<u32>),
+    LoadLevel(String),
+}
+
+impl Action {
+    fn from_object(object: &TiledObject) -> Option<Action> {
+        let action = object.string_property("action")?;
+        let target = object.object_property("target");
+        let value = object.string_property("value").map(str::to_string);
+
+        let parsed = match action.to_ascii_lowercase().as_str() {
+            "toggle" => target.map(Action::Toggle),
+            "activate" => target.map(Action::Activate),
+            "deactivate" => target.map(Action::Deactivate),
+            "sound" => value.map(Action::PlaySound),
+            "checkpoint" => Some(Action::SetCheckpoint(target)),
+            "load_level" => value.map(Action::LoadLevel),
+            _ => None,
+        };
+
+        if parsed.is_none() {
+            log::warn!(
+                "Object {}: invalid action {action}, missing target or value",
+                object.id
+            );
+        }
+
+        parsed
+    }
+}
+
+/// Ce que fait un déclencheur ou un écouteur quand il se déclenche
+#[derive(Component, Clone, Debug)]
+pub struct Script {
+    pub condition: Option<Condition>,
+    pub event: Option<String>,
+    pub action: Option<Action>,
+}
+
+impl Script {
+    fn from_object(object: &TiledObject) -> Script {
+        let condition = object.string_property("condition").and_then(|condition| {
+            let parsed = Condition::parse(condition);
+            if parsed.is_none() {
+                log::warn!("Object {}: invalid condition {condition}", object.id);
+            }
+            parsed
+        });
+
+        Script {
+            condition,
+            event: object
+                .string_property("event")
+                .filter(|event| !event.is_empty())
+                .map(str::to_string),
+            action: Action::from_object(object),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Trigger {
+    pub on: TriggerOn,
+    pub once: bool,
+    pub interval: f32,
+    inside: bool,
+    /// Temps passé dans la zone depuis le dernier déclenchement en mode "stay"
+    elapsed: f32,
+    done: bool,
+}
+
+#[derive(Component)]
+pub struct Listener {
+    pub listen: String,
+}
+
+pub fn setup_triggers(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledObject), Added<TiledObject>>,
+) {
+    for (entity, object) in &query {
+        if object.is("trigger") || object.is("triggers") {
+            let on = match object
+                .string_property("on")
+                .unwrap_or("enter")
+                .to_ascii_lowercase()
+                .as_str()
+            {
+                "exit" => TriggerOn::Exit,
+                "stay" => TriggerOn::Stay,
+                _ => TriggerOn::Enter,
+            };
+
+            commands.entity(entity).insert((
+                Trigger {
+                    on,
+                    once: object.bool_property("once").unwrap_or(true),
+                    interval: object
+                        .float_property("interval")
+                        .unwrap_or(DEFAULT_STAY_INTERVAL),
+                    inside: false,
+                    elapsed: 0.0,
+                    done: false,
+                },
+                Script::from_object(object),
+                Active(object.bool_property("active").unwrap_or(true)),
+            ));
+        } else if object.is("listener") || object.is("listeners") {
+            let Some(listen) = object.string_property("listen") else {
+                log::warn!("Listener {} has no listen property", object.id);
+                continue;
+            };
+
+            commands.entity(entity).insert((
+                Listener {
+                    listen: listen.to_string(),
+                },
+                Script::from_object(object),
+                Active(object.bool_property("active").unwrap_or(true)),
+            ));
+        }
+    }
+}
+
+/// Suit l'entrée et la sortie du joueur dans les déclencheurs
+pub fn update_triggers(
+    time: Res<Time>,
+    mut scripts: EventWriter<RunScript>,
+    player: Query<&Transform, With<Player>>,
+    mut triggers: Query<(
+        Entity,
+        &mut Trigger,
+        &Active,
+        &TiledObject,
+        &GlobalTransform,
+    )>,
+) {
+    if player.is_empty() {
+        return;
+    }
+
+    let position = player.single().translation.truncate();
+
+    for (entity, mut trigger, active, object, transform) in &mut triggers {
+        let inside = object_rect(object, transform).contains(position);
+        let entered = inside && !trigger.inside;
+        let exited = !inside && trigger.inside;
+        trigger.inside = inside;
+
+        if trigger.done || !active.0 {
+            continue;
+        }
+
+        let fire = match trigger.on {
+            TriggerOn::Enter => entered,
+            TriggerOn::Exit => exited,
+            TriggerOn::Stay => {
+                if !inside {
+                    trigger.elapsed = 0.0;
+                    false
+                } else if entered {
+                    trigger.elapsed = 0.0;
+                    true
+                } else {
+                    trigger.elapsed += time.delta_seconds();
+                    if trigger.elapsed >= trigger.interval {
+                        trigger.elapsed = 0.0;
+                        true
+                    } else {
+                        false
+                    }
+                }
+            }
+        };
+
+        if fire {
+            scripts.send(RunScript { entity });
+        }
+    }
+}
+
+/// Les écouteurs se déclenchent sur les événements nommés qu'ils attendent
+pub fn listen_events(
+    mut events: EventReader<TriggerEvent>,
+    mut scripts: EventWriter<RunScript>,
+    listeners: Query<(Entity, &Listener, &Active)>,
+) {
+    for event in events.read() {
+        for (entity, listener, active) in &listeners {
+            if active.0 && listener.listen == event.name {
+                scripts.send(RunScript { entity });
+            }
+        }
+    }
+}
+
+/// Vérifie la condition puis lance l'événement et l'action des scripts déclenchés
+#[allow(clippy::too_many_arguments)]
+pub fn run_scripts(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut levels: ResMut<LevelManager>,
+    mut checkpoint: ResMut<Checkpoint>,
+    mut flags: ResMut<TriggerFlags>,
+    mut requests: EventReader<RunScript>,
+    mut events: EventWriter<TriggerEvent>,
+    scripts: Query<(&Script, &GlobalTransform)>,
+    mut triggers: Query<&mut Trigger>,
+    objects: Query<(Entity, &TiledObject, &GlobalTransform)>,
+    mut actives: Query<&mut Active>,
+    collectibles: Query<&Collectible>,
+) {
+    let collected = |collectible: &Collectible| collectible.state == CollectibleState::Collected;
+
+    for request in requests.read() {
+        let Ok((script, transform)) = scripts.get(request.entity) else {
+            continue;
+        };
+
+        let allowed = match &script.condition {
+            None => true,
+            Some(Condition::Flag(flag)) => flags.0.contains(flag),
+            Some(Condition::NotFlag(flag)) => !flags.0.contains(flag),
+            Some(Condition::Collected(count)) => {
+                collectibles.iter().filter(|c| collected(c)).count() >= *count
+            }
+            Some(Condition::AllCollected) => {
+                !collectibles.is_empty() && collectibles.iter().all(collected)
+            }
+        };
+
+        if !allowed {
+            continue;
+        }
+
+        if let Ok(mut trigger) = triggers.get_mut(request.entity) {
+            trigger.done = trigger.once;
+        }
+
+        if let Some(event) = &script.event {
+            flags.0.insert(event.clone());
+            events.send(TriggerEvent {
+                name: event.clone(),
+            });
+        }
+
+        let Some(action) = &script.action else {
+            continue;
+        };
+
+        let find = |id: u32| {
+            let found = objects.iter().find(|(_, object, ..)| object.id == id);
+            if found.is_none() {
+                log::warn!("Script target {id} not found");
+            }
+            found
+        };
+
+        match action {
+            Action::Toggle(id) | Action::Activate(id) | Action::Deactivate(id) => {
+                let Some((entity, ..)) = find(*id) else {
+                    continue;
+                };
+                // modifié sur place pour que deux bascules dans la même frame s'annulent
+                let Ok(mut active) = actives.get_mut(entity) else {
+                    log::warn!("Script target {id} can't be activated");
+                    continue;
+                };
+
+                active.0 = match action {
+                    Action::Toggle(_) => !active.0,
+                    Action::Activate(_) => true,
+                    _ => false,
+                };
+            }
+            Action::PlaySound(path) => {
+                commands.spawn(AudioBundle {
+                    source: asset_server.load(path.clone()),
+                    settings: PlaybackSettings::DESPAWN,
+                });
+            }
+            Action::SetCheckpoint(target) => {
+                let position = match target {
+                    Some(id) => match find(*id) {
+                        Some((_, _, target_transform)) => target_transform.translation(),
+                        None => continue,
+                    },
+                    None => transform.translation(),
+                };
+                checkpoint.0 = position.truncate();
+            }
+            Action::LoadLevel(path) => levels.load_level(path.clone()),
+        }
+    }
+}
+
+/// Les événements lancés ne valent que pour le niveau où ils l'ont été
+pub fn reset_flags(mut unloaded: EventReader<LevelUnloaded>, mut flags: ResMut<TriggerFlags>) {
+    if unloaded.read().count() > 0 {
+        flags.0.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_flags() {
+        assert_eq!(
+            Condition::parse("flag:door"),
+            Some(Condition::Flag("door".to_string()))
+        );
+        assert_eq!(
+            Condition::parse(" !flag : door "),
+            Some(Condition::NotFlag("door".to_string()))
+        );
+        assert_eq!(
+            Condition::parse("FLAG:door"),
+            Some(Condition::Flag("door".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_collected() {
+        assert_eq!(
+            Condition::parse("collected:3"),
+            Some(Condition::Collected(3))
+        );
+        assert_eq!(
+            Condition::parse("All_Collected"),
+            Some(Condition::AllCollected)
+        );
+        assert_eq!(Condition::parse("collected:many"), None);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(Condition::parse(""), None);
+        assert_eq!(Condition::parse("door"), None);
+        assert_eq!(Condition::parse("unknown:door"), None);
+    }
+}
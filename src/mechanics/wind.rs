@@ -0,0 +1,181 @@
+// zones de vent : objets rectangles de classe "wind_zone", tant que le joueur est dedans une
+// force lui est appliquée (vent horizontal, courant ascendant...)
+//
+// propriétés reconnues :
+// - force_x, force_y (float) : accélération en pixels par seconde au carré
+// - oscillation (float) : variation de la force entre 0 (constante) et 1 (s'annule par moments)
+// - period (float) : durée d'une oscillation en secondes
+// - active (bool) : la zone est active au chargement, les triggers peuvent la changer
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::mechanics::Active;
+use crate::particles::{Curve, Gradient, ParticleEmitter, ParticleSettings};
+use crate::player::Player;
+use crate::tile::objects::{object_rect, TiledObject};
+
+const DEFAULT_PERIOD: f32 = 2.0;
+
+/// Particules émises par seconde pour 1000 pixels carrés de zone
+const PARTICLE_DENSITY: f32 = 0.5;
+/// Vitesse des particules par unité de force
+const PARTICLE_SPEED_FACTOR: f32 = 0.25;
+
+#[derive(Component)]
+pub struct WindZone {
+    pub force: Vec2,
+    pub oscillation: f32,
+    pub period: f32,
+}
+
+impl WindZone {
+    fn current_force(&self, time: f32) -> Vec2 {
+        if self.oscillation <= 0.0 || self.period <= 0.0 {
+            return self.force;
+        }
+
+        // oscille entre (1 - oscillation) et 1 fois la force
+        let wave = ((time / self.period) * TAU).sin() * 0.5 + 0.5;
+        self.force * (1.0 - self.oscillation * wave)
+    }
+}
+
+/// Émetteur de particules qui montre la direction et la force d'une zone de vent
+#[derive(Component)]
+pub struct WindParticles;
+
+/// Accélération du vent sur le joueur pendant cette frame, appliquée dans la chaîne de
+/// mouvement du joueur
+#[derive(Resource, Default)]
+pub struct PlayerWind(pub Vec2);
+
+pub fn setup_wind_zones(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledObject), Added<TiledObject>>,
+) {
+    for (entity, object) in &query {
+        if !object.is("wind_zone") && !object.is("wind_zones") {
+            continue;
+        }
+
+        let zone = WindZone {
+            force: Vec2::new(
+                object.float_property("force_x").unwrap_or(0.0),
+                object.float_property("force_y").unwrap_or(0.0),
+            ),
+            oscillation: object
+                .float_property("oscillation")
+                .unwrap_or(0.0)
+                .clamp(0.0, 1.0),
+            period: object.float_property("period").unwrap_or(DEFAULT_PERIOD),
+        };
+
+        let settings = ParticleSettings {
+            lifetime: (0.6, 1.0),
+            direction: zone.force.normalize_or_zero(),
+            spread: 0.05,
+            area: object.size / 2.0,
+            color: Gradient::constant(Color::rgba(1.0, 1.0, 1.0, 0.6)),
+            alpha: Curve::fade_out(),
+            ..Default::default()
+        };
+        let rate = object.size.x * object.size.y / 1000.0 * PARTICLE_DENSITY;
+
+        let particles = commands
+            .spawn((
+                Name::new("Wind particles"),
+                WindParticles,
+                ParticleEmitter::continuous(settings, rate),
+                TransformBundle::default(),
+            ))
+            .id();
+
+        commands
+            .entity(entity)
+            .insert((zone, Active(object.bool_property("active").unwrap_or(true))))
+            .add_child(particles);
+    }
+}
+
+/// Additionne la force des zones actives qui contiennent le joueur
+pub fn update_player_wind(
+    time: Res<Time>,
+    mut wind: ResMut<PlayerWind>,
+    player: Query<&Transform, With<Player>>,
+    zones: Query<(&WindZone, &Active, &TiledObject, &GlobalTransform)>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+
+    let position = player.translation.truncate();
+    let elapsed = time.elapsed_seconds();
+
+    let force = zones
+        .iter()
+        .filter(|(_, active, object, transform)| {
+            active.0 && object_rect(object, transform).contains(position)
+        })
+        .map(|(zone, ..)| zone.current_force(elapsed))
+        .sum();
+
+    if wind.0 != force {
+        wind.0 = force;
+    }
+}
+
+/// Les particules suivent la force actuelle de la zone et s'arrêtent quand elle est inactive
+pub fn update_wind_particles(
+    time: Res<Time>,
+    zones: Query<(&WindZone, &Active)>,
+    mut emitters: Query<(&Parent, &mut ParticleEmitter), With<WindParticles>>,
+) {
+    for (parent, mut emitter) in &mut emitters {
+        let Ok((zone, active)) = zones.get(parent.get()) else {
+            continue;
+        };
+
+        let force = zone.current_force(time.elapsed_seconds());
+        let speed = force.length() * PARTICLE_SPEED_FACTOR;
+
+        emitter.active = active.0 && speed > 0.0;
+        emitter.settings.direction = force.normalize_or_zero();
+        emitter.settings.speed = (speed * 0.8, speed * 1.2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(oscillation: f32, period: f32) -> WindZone {
+        WindZone {
+            force: Vec2::new(100.0, 0.0),
+            oscillation,
+            period,
+        }
+    }
+
+    #[test]
+    fn constant_without_oscillation() {
+        assert_eq!(zone(0.0, 2.0).current_force(0.7), Vec2::new(100.0, 0.0));
+        assert_eq!(zone(0.5, 0.0).current_force(0.7), Vec2::new(100.0, 0.0));
+    }
+
+    #[test]
+    fn oscillates_between_bounds() {
+        let zone = zone(0.5, 4.0);
+
+        // sommet de la sinusoïde : force la plus faible
+        assert!((zone.current_force(1.0).x - 50.0).abs() < 1e-3);
+        // creux : force complète
+        assert!((zone.current_force(3.0).x - 100.0).abs() < 1e-3);
+
+        for step in 0..40 {
+            let force = zone.current_force(step as f32 * 0.1).x;
+            assert!((50.0 - 1e-3..=100.0 + 1e-3).contains(&force));
+        }
+    }
+}
@@ -0,0 +1,420 @@
+// système de particules simple, tout est calculé sur le CPU et affiché avec des sprites sans
+// texture, bevy les regroupe dans un seul batch donc ça reste léger même sans GPU dédié
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+/// Z des particules, devant le décor mais derrière le joueur
+const PARTICLE_Z: f32 = -0.2;
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ParticleRng(0x2545_f491_4f6c_dd1d))
+            .add_event::<ParticleBurst>()
+            .add_systems(
+                Update,
+                (spawn_bursts, emit_particles, update_particles).chain(),
+            );
+    }
+}
+
+/// Générateur pseudo-aléatoire (xorshift), suffisant pour des particules
+#[derive(Resource)]
+pub struct ParticleRng(u64);
+
+impl ParticleRng {
+    /// Renvoie un nombre entre 0 et 1
+    pub fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Renvoie un nombre entre min et max
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next()
+    }
+}
+
+/// Courbe définie par des points (temps entre 0 et 1, valeur), interpolée linéairement
+#[derive(Clone)]
+pub struct Curve(pub Vec<(f32, f32)>);
+
+impl Curve {
+    /// Courbe qui part de 1 et descend jusqu'a 0
+    pub fn fade_out() -> Curve {
+        Curve(vec![(0.0, 1.0), (1.0, 0.0)])
+    }
+
+    pub fn sample(&self, t: f32) -> f32 {
+        sample_keys(&self.0, t, |a, b, t| a + (b - a) * t).unwrap_or(1.0)
+    }
+}
+
+/// Dégradé de couleurs défini par des points (temps entre 0 et 1, couleur)
+#[derive(Clone)]
+pub struct Gradient(pub Vec<(f32, Color)>);
+
+impl Gradient {
+    pub fn constant(color: Color) -> Gradient {
+        Gradient(vec![(0.0, color)])
+    }
+
+    pub fn sample(&self, t: f32) -> Color {
+        sample_keys(&self.0, t, |a, b, t| {
+            let a = a.as_rgba_f32();
+            let b = b.as_rgba_f32();
+            Color::rgba(
+                a[0] + (b[0] - a[0]) * t,
+                a[1] + (b[1] - a[1]) * t,
+                a[2] + (b[2] - a[2]) * t,
+                a[3] + (b[3] - a[3]) * t,
+            )
+        })
+        .unwrap_or(Color::WHITE)
+    }
+}
+
+/// Interpole entre les deux points qui entourent t, les points doivent être triés
+fn sample_keys<T: Copy>(keys: &[(f32, T)], t: f32, lerp: impl Fn(T, T, f32) -> T) -> Option<T> {
+    let first = keys.first()?;
+    if t <= first.0 {
+        return Some(first.1);
+    }
+
+    for window in keys.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        if t <= end.0 {
+            let span = end.0 - start.0;
+            let local = if span > 0.0 {
+                (t - start.0) / span
+            } else {
+                1.0
+            };
+            return Some(lerp(start.1, end.1, local));
+        }
+    }
+
+    keys.last().map(|key| key.1)
+}
+
+/// Paramètres des particules émises
+#[derive(Clone)]
+pub struct ParticleSettings {
+    /// Durée de vie min et max en secondes
+    pub lifetime: (f32, f32),
+    /// Vitesse initiale min et max en pixels/s
+    pub speed: (f32, f32),
+    /// Direction du cône d'émission
+    pub direction: Vec2,
+    /// Demi-angle du cône d'émission en radians, PI pour émettre dans toutes les directions
+    pub spread: f32,
+    /// Zone d'apparition autour de l'émetteur (demi-taille)
+    pub area: Vec2,
+    pub gravity: Vec2,
+    /// Freinage appliqué a la vitesse, en fraction par seconde
+    pub drag: f32,
+    pub size: f32,
+    /// Couleur sur la durée de vie de la particule
+    pub color: Gradient,
+    /// Alpha sur la durée de vie de la particule, multiplié a celui de la couleur
+    pub alpha: Curve,
+}
+
+impl Default for ParticleSettings {
+    fn default() -> Self {
+        ParticleSettings {
+            lifetime: (0.3, 0.5),
+            speed: (20.0, 40.0),
+            direction: Vec2::Y,
+            spread: PI,
+            area: Vec2::ZERO,
+            gravity: Vec2::ZERO,
+            drag: 0.0,
+            size: 1.0,
+            color: Gradient::constant(Color::WHITE),
+            alpha: Curve::fade_out(),
+        }
+    }
+}
+
+/// Effets prédéfinis utilisés par le jeu
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParticlePreset {
+    RunDust,
+    JumpPuff,
+    LandPuff,
+    DashBurst,
+    OrbPickup,
+    Death,
+}
+
+impl ParticlePreset {
+    pub fn settings(&self) -> ParticleSettings {
+        let dust = Color::rgb(0.85, 0.85, 0.8);
+
+        match self {
+            ParticlePreset::RunDust => ParticleSettings {
+                lifetime: (0.2, 0.4),
+                speed: (5.0, 15.0),
+                direction: Vec2::Y,
+                spread: PI / 3.0,
+                area: Vec2::new(2.0, 0.0),
+                gravity: Vec2::new(0.0, -20.0),
+                color: Gradient::constant(dust),
+                ..Default::default()
+            },
+            ParticlePreset::JumpPuff => ParticleSettings {
+                lifetime: (0.2, 0.35),
+                speed: (20.0, 40.0),
+                direction: Vec2::Y,
+                spread: PI / 2.0,
+                area: Vec2::new(4.0, 0.0),
+                drag: 4.0,
+                color: Gradient::constant(dust),
+                ..Default::default()
+            },
+            ParticlePreset::LandPuff => ParticleSettings {
+                lifetime: (0.25, 0.4),
+                speed: (25.0, 50.0),
+                direction: Vec2::Y,
+                spread: PI / 2.2,
+                area: Vec2::new(5.0, 0.0),
+                gravity: Vec2::new(0.0, -60.0),
+                drag: 5.0,
+                color: Gradient::constant(dust),
+                ..Default::default()
+            },
+            ParticlePreset::DashBurst => ParticleSettings {
+                lifetime: (0.2, 0.4),
+                speed: (40.0, 80.0),
+                spread: PI,
+                drag: 6.0,
+                size: 2.0,
+                color: Gradient(vec![
+                    (0.0, Color::WHITE),
+                    (0.5, Color::rgb(0.27, 0.72, 1.0)),
+                ]),
+                ..Default::default()
+            },
+            ParticlePreset::OrbPickup => ParticleSettings {
+                lifetime: (0.4, 0.7),
+                speed: (30.0, 60.0),
+                spread: PI,
+                drag: 3.0,
+                color: Gradient(vec![(0.0, Color::WHITE), (0.4, Color::rgb(1.0, 0.85, 0.3))]),
+                alpha: Curve(vec![(0.0, 1.0), (0.6, 1.0), (1.0, 0.0)]),
+                ..Default::default()
+            },
+            ParticlePreset::Death => ParticleSettings {
+                lifetime: (0.5, 0.9),
+                speed: (60.0, 120.0),
+                spread: PI,
+                gravity: Vec2::new(0.0, -80.0),
+                drag: 2.5,
+                size: 2.0,
+                color: Gradient(vec![
+                    (0.0, Color::WHITE),
+                    (0.3, Color::rgb(0.67, 0.2, 0.2)),
+                    (1.0, Color::rgb(0.3, 0.1, 0.15)),
+                ]),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Nombre de particules émises d'un coup par défaut
+    pub fn burst_count(&self) -> u32 {
+        match self {
+            ParticlePreset::RunDust => 1,
+            ParticlePreset::JumpPuff => 6,
+            ParticlePreset::LandPuff => 8,
+            ParticlePreset::DashBurst => 12,
+            ParticlePreset::OrbPickup => 16,
+            ParticlePreset::Death => 32,
+        }
+    }
+}
+
+/// Émetteur de particules, les particules sont émises dans le monde et ne suivent pas l'émetteur
+#[derive(Component)]
+pub struct ParticleEmitter {
+    pub settings: ParticleSettings,
+    /// Particules émises par seconde
+    pub rate: f32,
+    /// Particules émises d'un coup au premier update de l'émetteur
+    pub burst: u32,
+    /// Un émetteur inactif n'émet plus mais ses particules continuent de vivre
+    pub active: bool,
+    /// Despawn l'émetteur une fois le burst émis, pour les effets ponctuels
+    pub one_shot: bool,
+
+    accumulator: f32,
+    burst_done: bool,
+}
+
+impl ParticleEmitter {
+    /// Émetteur continu
+    pub fn continuous(settings: ParticleSettings, rate: f32) -> ParticleEmitter {
+        ParticleEmitter {
+            settings,
+            rate,
+            burst: 0,
+            active: true,
+            one_shot: false,
+            accumulator: 0.0,
+            burst_done: true,
+        }
+    }
+
+    /// Émetteur qui émet toutes ses particules d'un coup puis disparait
+    pub fn burst(settings: ParticleSettings, count: u32) -> ParticleEmitter {
+        ParticleEmitter {
+            settings,
+            rate: 0.0,
+            burst: count,
+            active: true,
+            one_shot: true,
+            accumulator: 0.0,
+            burst_done: false,
+        }
+    }
+}
+
+/// Event pour faire apparaître un effet ponctuel a une position
+#[derive(Event)]
+pub struct ParticleBurst {
+    pub preset: ParticlePreset,
+    pub position: Vec2,
+    /// Direction du cône, None garde celle du preset
+    pub direction: Option<Vec2>,
+}
+
+impl ParticleBurst {
+    pub fn new(preset: ParticlePreset, position: Vec2) -> ParticleBurst {
+        ParticleBurst {
+            preset,
+            position,
+            direction: None,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Particle {
+    velocity: Vec2,
+    gravity: Vec2,
+    drag: f32,
+    age: f32,
+    lifetime: f32,
+    color: Gradient,
+    alpha: Curve,
+}
+
+fn spawn_bursts(mut commands: Commands, mut events: EventReader<ParticleBurst>) {
+    for event in events.read() {
+        let mut settings = event.preset.settings();
+        if let Some(direction) = event.direction {
+            settings.direction = direction;
+        }
+
+        commands.spawn((
+            Name::new(format!("{:?} particles", event.preset)),
+            ParticleEmitter::burst(settings, event.preset.burst_count()),
+            TransformBundle::from_transform(Transform::from_translation(
+                event.position.extend(PARTICLE_Z),
+            )),
+        ));
+    }
+}
+
+fn emit_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut rng: ResMut<ParticleRng>,
+    mut query: Query<(Entity, &mut ParticleEmitter, &GlobalTransform)>,
+) {
+    for (entity, mut emitter, transform) in &mut query {
+        let mut count = 0;
+
+        if !emitter.burst_done {
+            count += emitter.burst;
+            emitter.burst_done = true;
+        }
+
+        if emitter.active && emitter.rate > 0.0 {
+            emitter.accumulator += emitter.rate * time.delta_seconds();
+            let emitted = emitter.accumulator.floor();
+            emitter.accumulator -= emitted;
+            count += emitted as u32;
+        }
+
+        let origin = transform.translation().truncate();
+        let settings = &emitter.settings;
+        let base_angle = settings.direction.y.atan2(settings.direction.x);
+
+        for _ in 0..count {
+            let angle = base_angle + rng.range(-settings.spread, settings.spread);
+            let speed = rng.range(settings.speed.0, settings.speed.1);
+            let offset = Vec2::new(
+                rng.range(-settings.area.x, settings.area.x),
+                rng.range(-settings.area.y, settings.area.y),
+            );
+
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: settings.color.sample(0.0),
+                        custom_size: Some(Vec2::splat(settings.size)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation((origin + offset).extend(PARTICLE_Z)),
+                    ..Default::default()
+                },
+                Particle {
+                    velocity: Vec2::new(angle.cos(), angle.sin()) * speed,
+                    gravity: settings.gravity,
+                    drag: settings.drag,
+                    age: 0.0,
+                    lifetime: rng.range(settings.lifetime.0, settings.lifetime.1),
+                    color: settings.color.clone(),
+                    alpha: settings.alpha.clone(),
+                },
+            ));
+        }
+
+        if emitter.one_shot {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let delta = time.delta_seconds();
+
+    for (entity, mut particle, mut transform, mut sprite) in &mut query {
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let gravity = particle.gravity;
+        let drag = (1.0 - particle.drag * delta).max(0.0);
+        particle.velocity = (particle.velocity + gravity * delta) * drag;
+        transform.translation += (particle.velocity * delta).extend(0.0);
+
+        let t = particle.age / particle.lifetime;
+        let mut color = particle.color.sample(t);
+        color.set_a(color.a() * particle.alpha.sample(t));
+        sprite.color = color;
+    }
+}
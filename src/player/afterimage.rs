@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+
+use crate::particles::Gradient;
+use crate::player::dash::Dash;
+use crate::player::*;
+
+/// Z des silhouettes, derrière le joueur et sa mèche
+const AFTERIMAGE_Z: f32 = -1.0;
+
+/// Réglages des silhouettes laissées pendant le dash
+#[derive(Resource)]
+pub struct AfterimageSettings {
+    /// Temps entre deux silhouettes en secondes
+    pub interval: f32,
+    /// Durée de vie d'une silhouette en secondes
+    pub duration: f32,
+    /// Couleur de la silhouette sur sa durée de vie, l'alpha est pris en compte
+    pub gradient: Gradient,
+    /// Nombre de silhouettes créées au lancement du jeu, augmenté si la durée de vie en demande
+    /// plus
+    pub pool_size: usize,
+}
+
+impl Default for AfterimageSettings {
+    fn default() -> Self {
+        AfterimageSettings {
+            interval: 0.05,
+            duration: 0.3,
+            gradient: Gradient(vec![
+                (0.0, Color::rgba(1.0, 1.0, 1.0, 0.8)),
+                (0.3, Color::rgba(0.27, 0.72, 1.0, 0.6)),
+                (1.0, Color::rgba(0.0, 0.0, 1.0, 0.0)),
+            ]),
+            pool_size: 8,
+        }
+    }
+}
+
+/// Silhouettes disponibles, elles sont cachées et réutilisées plutôt que despawn
+#[derive(Resource, Default)]
+pub struct AfterimagePool {
+    free: Vec<Entity>,
+    /// Temps écoulé depuis la dernière silhouette
+    timer: f32,
+}
+
+#[derive(Component, Default)]
+pub struct Afterimage {
+    age: f32,
+}
+
+fn afterimage_bundle() -> impl Bundle {
+    (
+        Name::new("Afterimage"),
+        SpriteSheetBundle {
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        Afterimage::default(),
+    )
+}
+
+/// Remplit la pool au lancement pour ne pas spawn pendant le premier dash, au moins assez de
+/// silhouettes pour couvrir leur durée de vie
+pub fn setup_afterimages(
+    mut commands: Commands,
+    settings: Res<AfterimageSettings>,
+    mut pool: ResMut<AfterimagePool>,
+) {
+    let needed = (settings.duration / settings.interval).ceil() as usize + 1;
+
+    for _ in 0..settings.pool_size.max(needed) {
+        let entity = commands.spawn(afterimage_bundle()).id();
+        pool.free.push(entity);
+    }
+}
+
+/// Prend une photo de la frame actuelle du joueur a intervalle régulier pendant le dash
+pub fn spawn_afterimages(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<AfterimageSettings>,
+    mut pool: ResMut<AfterimagePool>,
+    player: Query<
+        (
+            &Transform,
+            &Sprite,
+            &Handle<Image>,
+            &TextureAtlas,
+            Ref<Dash>,
+        ),
+        With<Player>,
+    >,
+    mut afterimages: Query<
+        (
+            &mut Afterimage,
+            &mut Transform,
+            &mut Sprite,
+            &mut Handle<Image>,
+            &mut TextureAtlas,
+            &mut Visibility,
+        ),
+        Without<Player>,
+    >,
+) {
+    if player.is_empty() {
+        return;
+    }
+
+    let (transform, sprite, texture, atlas, dash) = player.single();
+
+    // la première silhouette apparait dès le début du dash
+    if dash.is_added() {
+        pool.timer = settings.interval;
+    } else {
+        pool.timer += time.delta_seconds();
+    }
+
+    if pool.timer < settings.interval {
+        return;
+    }
+    pool.timer -= settings.interval;
+
+    let image_transform = Transform {
+        translation: transform.translation.truncate().extend(AFTERIMAGE_Z),
+        scale: transform.scale,
+        ..Default::default()
+    };
+    // on garde le flip et la taille du sprite du joueur
+    let image_sprite = Sprite {
+        color: settings.gradient.sample(0.0),
+        ..sprite.clone()
+    };
+
+    let pooled = pool
+        .free
+        .pop()
+        .and_then(|entity| afterimages.get_mut(entity).ok());
+
+    let Some((
+        mut afterimage,
+        mut pooled_transform,
+        mut pooled_sprite,
+        mut pooled_texture,
+        mut pooled_atlas,
+        mut visibility,
+    )) = pooled
+    else {
+        // la pool est vide, la nouvelle silhouette est créée directement a la bonne place pour
+        // ne pas laisser de trou dans la traînée, elle rejoindra la pool en disparaissant
+        commands.spawn((
+            Name::new("Afterimage"),
+            SpriteSheetBundle {
+                transform: image_transform,
+                sprite: image_sprite,
+                texture: texture.clone(),
+                atlas: atlas.clone(),
+                visibility: Visibility::Visible,
+                ..Default::default()
+            },
+            Afterimage::default(),
+        ));
+        return;
+    };
+
+    afterimage.age = 0.0;
+    *pooled_transform = image_transform;
+    *pooled_sprite = image_sprite;
+    *pooled_texture = texture.clone();
+    *pooled_atlas = atlas.clone();
+    *visibility = Visibility::Visible;
+}
+
+/// Fait disparaître les silhouettes selon le dégradé et les remet dans la pool
+pub fn fade_afterimages(
+    time: Res<Time>,
+    settings: Res<AfterimageSettings>,
+    mut pool: ResMut<AfterimagePool>,
+    mut query: Query<(Entity, &mut Afterimage, &mut Sprite, &mut Visibility)>,
+) {
+    for (entity, mut afterimage, mut sprite, mut visibility) in &mut query {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+
+        afterimage.age += time.delta_seconds();
+
+        if afterimage.age >= settings.duration {
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+        } else {
+            sprite.color = settings.gradient.sample(afterimage.age / settings.duration);
+        }
+    }
+}
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use bevy_spritesheet_animation::prelude::*;
 
 use crate::player::{*, Direction};
-use crate::player::dash::{DashCooldown, DashTrail};
+use crate::player::dash::DashCooldown;
 
 /// Change l'animation en fonction du state du joueur
 pub fn update_animation(
@@ -147,7 +147,7 @@ pub fn update_sprite_direction(mut query: Query<(&mut Sprite, &Direction)>) {
 }
 
 /// Met à jour la couleur du sprite pendant le dash
-pub fn update_dash_color(mut query: Query<&mut Sprite, With<Dash>>) {
+pub fn update_dash_color(mut query: Query<&mut Sprite, (With<Player>, With<Dash>)>) {
     if query.is_empty() {
         return;
     }
@@ -161,7 +161,7 @@ pub fn update_dash_color(mut query: Query<&mut Sprite, With<Dash>>) {
 
 /// Reset la couleur du dash après celui ci
 pub fn reset_dash_color(
-    mut query: Query<&mut Sprite, (Without<Dash>, Without<DashCooldown>, Without<DashTrail>)>,
+    mut query: Query<&mut Sprite, (With<Player>, Without<Dash>, Without<DashCooldown>)>,
 ) {
     if query.is_empty() {
         return;
@@ -3,13 +3,7 @@ use bevy_rapier2d::prelude::*;
 
 use crate::player::{*, Direction};
 
-/// Contrôle la fréquence de spawn des silhouettes de dash
-/// Plus la valeur est haute moins les silhouettes sont fréquentes
-const TRAIL_FREQUENCY: usize = 7;
-
-/// Valeur de réduction de l'alpha des silhouettes
-/// Plus la valeur est haute plus les silhouettes restent longtemps
-const ALPHA_DECREMENT: f32 = 0.04;
+use super::riding::Momentum;
 
 #[derive(Component)]
 pub struct Dash {
@@ -20,9 +14,6 @@ pub struct Dash {
 #[derive(Component, Default)]
 pub struct DashCooldown(f32);
 
-#[derive(Component)]
-pub struct DashTrail;
-
 impl Dash {
     pub fn new(direction: DashDirection) -> Dash {
         Dash {
@@ -67,13 +58,18 @@ pub fn dash(
     button_inputs: Res<ButtonInput<GamepadButton>>,
     axes: Res<Axis<GamepadAxis>>,
     // mouse: Res<ButtonInput<MouseButton>>,
-    query: Query<Entity, (Without<Dash>, Without<DashCooldown>, With<Player>)>,
+    mut query: Query<(Entity, &mut Player), (Without<Dash>, Without<DashCooldown>)>,
 ) {
     if query.is_empty() {
         return;
     }
 
-    let entity = query.single();
+    let (entity, mut player) = query.single_mut();
+
+    // plus de dash disponible, il faut retoucher le sol
+    if player.dashes == 0 {
+        return;
+    }
 
     let mut dash: bool = false;
 
@@ -173,6 +169,8 @@ pub fn dash(
                     .entity(entity)
                     .insert(direction.get_direction().unwrap());
             }
+            player.dashes -= 1;
+
             commands
                 .entity(entity)
                 .remove::<Jump>()
@@ -184,7 +182,7 @@ pub fn dash(
 }
 
 pub fn dashing(
-    mut query: Query<(Entity, &mut Velocity, &Player, &mut Dash)>,
+    mut query: Query<(Entity, &mut Velocity, &Player, &mut Dash, Option<&Momentum>)>,
     mut commands: Commands,
     time: Res<Time>,
 ) {
@@ -192,7 +190,7 @@ pub fn dashing(
         return;
     }
 
-    let (entity, mut velocity, player, mut dash) = query.single_mut();
+    let (entity, mut velocity, player, mut dash, momentum) = query.single_mut();
 
     let movement = match dash.direction {
         DashDirection::North => Vec2::new(0.0, 1.0),
@@ -214,6 +212,10 @@ pub fn dashing(
     } else {
         dash.elapsed += time.delta_seconds();
         velocity.linvel = movement * player.dash_speed;
+        // élan d'une plateforme mobile quittée en dashant
+        if let Some(Momentum(momentum)) = momentum {
+            velocity.linvel += *momentum;
+        }
     }
 }
 
@@ -234,49 +236,15 @@ pub fn dash_cooldown(
     }
 }
 
-pub fn spawn_dash_trail(
-    query: Query<(&Transform, &Dash, &Direction)>,
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-) {
+/// Recharge les dashs quand le joueur touche le sol
+pub fn refill_dash(mut query: Query<&mut Player, Without<Dash>>) {
     if query.is_empty() {
         return;
     }
 
-    let (transform, dash, direction) = query.single();
+    let mut player = query.single_mut();
 
-    if (dash.elapsed * 100.0) as usize % TRAIL_FREQUENCY == 0 {
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform {
-                    translation: Vec3::new(transform.translation.x, transform.translation.y, -1.0),
-                    ..Default::default()
-                },
-                texture: asset_server.load("player_dash.png"),
-                ..Default::default()
-            },
-            DashTrail,
-            direction.clone(),
-        ));
-    }
-}
-
-pub fn fade_out_trail(
-    mut query: Query<(Entity, &mut Sprite), With<DashTrail>>,
-    mut commands: Commands,
-) {
-    if query.is_empty() {
-        return;
-    }
-
-    for (entity, mut sprite) in &mut query {
-        let mut alpha = sprite.color.a();
-        alpha -= ALPHA_DECREMENT;
-
-        if alpha <= 0.0 {
-            commands.entity(entity).despawn();
-        } else {
-            sprite.color = Color::rgba(0.0, 0.0, 1.0, alpha);
-        }
+    if player.grounded && player.dashes < player.max_dashes {
+        player.dashes = player.max_dashes;
     }
 }
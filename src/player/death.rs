@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::camera::CameraShakeEvent;
+use crate::particles::{ParticleBurst, ParticlePreset};
+use crate::player::dash::{Dash, DashCooldown};
+use crate::player::movement::JumpBuffer;
+use crate::player::*;
+use crate::room::Checkpoint;
+
+/// Envoyé quand le joueur meurt (chute, piques...), il réapparait au dernier checkpoint
+#[derive(Event)]
+pub struct PlayerDied;
+
+/// Fait réapparaître le joueur au checkpoint et remet son état a zéro
+pub fn respawn_player(
+    mut commands: Commands,
+    mut events: EventReader<PlayerDied>,
+    checkpoint: Res<Checkpoint>,
+    mut bursts: EventWriter<ParticleBurst>,
+    mut shakes: EventWriter<CameraShakeEvent>,
+    mut query: Query<(Entity, &mut Transform, &mut Velocity, &mut Player)>,
+) {
+    // plusieurs morts dans la même frame ne comptent qu'une fois
+    if events.read().count() == 0 || query.is_empty() {
+        return;
+    }
+
+    let (entity, mut transform, mut velocity, mut player) = query.single_mut();
+
+    bursts.send(ParticleBurst::new(
+        ParticlePreset::Death,
+        transform.translation.truncate(),
+    ));
+    shakes.send(CameraShakeEvent::new(0.6, 0.4));
+
+    transform.translation.x = checkpoint.0.x;
+    transform.translation.y = checkpoint.0.y;
+    velocity.linvel = Vec2::ZERO;
+    player.dashes = player.max_dashes;
+
+    commands
+        .entity(entity)
+        .remove::<(Dash, DashCooldown, Jump, JumpBuffer)>()
+        .insert(GravityScale(GRAVITY_SCALE))
+        .insert(PlayerState::Idle);
+}
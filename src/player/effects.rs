@@ -0,0 +1,133 @@
+use std::mem::{discriminant, Discriminant};
+
+use bevy::prelude::*;
+
+use bevy_rapier2d::prelude::*;
+
+use crate::camera::CameraShakeEvent;
+use crate::particles::{ParticleBurst, ParticleEmitter, ParticlePreset};
+use crate::player::dash::{Dash, DashDirection};
+use crate::player::*;
+
+/// Particules de poussière émises par seconde quand le joueur court
+const RUN_DUST_RATE: f32 = 12.0;
+
+/// Position des pieds du joueur par rapport au centre du sprite
+const FEET_OFFSET: Vec2 = Vec2::new(0.0, -8.0);
+
+/// Vitesse de chute a partir de laquelle l'atterrissage fait trembler la caméra
+const HEAVY_LANDING_SPEED: f32 = 200.0;
+
+/// Marque l'émetteur de poussière attaché aux pieds du joueur
+#[derive(Component)]
+pub struct RunDust;
+
+/// Attache un émetteur de poussière aux pieds du joueur quand il apparait
+pub fn spawn_run_dust(mut commands: Commands, query: Query<Entity, Added<Player>>) {
+    for entity in &query {
+        let preset = ParticlePreset::RunDust;
+        let mut emitter = ParticleEmitter::continuous(preset.settings(), RUN_DUST_RATE);
+        emitter.active = false;
+
+        let dust = commands
+            .spawn((
+                Name::new("Run dust"),
+                emitter,
+                RunDust,
+                TransformBundle::from_transform(Transform::from_translation(
+                    FEET_OFFSET.extend(0.0),
+                )),
+            ))
+            .id();
+
+        commands.entity(entity).add_child(dust);
+    }
+}
+
+/// Active la poussière uniquement quand le joueur court au sol
+pub fn update_run_dust(
+    players: Query<(&Player, &PlayerState)>,
+    mut emitters: Query<(&mut ParticleEmitter, &Parent), With<RunDust>>,
+) {
+    for (mut emitter, parent) in &mut emitters {
+        let Ok((player, state)) = players.get(parent.get()) else {
+            continue;
+        };
+
+        let running = player.grounded && *state == PlayerState::Run;
+        if emitter.active != running {
+            emitter.active = running;
+        }
+    }
+}
+
+/// Fait apparaître les effets ponctuels quand le state du joueur change
+pub fn emit_state_particles(
+    query: Query<(&Transform, &PlayerState), Changed<PlayerState>>,
+    mut bursts: EventWriter<ParticleBurst>,
+    // le state est réinséré a chaque frame par certains systèmes, on garde le précédent pour
+    // n'émettre qu'au changement réel
+    mut previous: Local<Option<Discriminant<PlayerState>>>,
+) {
+    for (transform, state) in &query {
+        let current = discriminant(state);
+        if *previous == Some(current) {
+            continue;
+        }
+        *previous = Some(current);
+
+        let position = transform.translation.truncate();
+
+        let preset = match state {
+            PlayerState::Jump => ParticlePreset::JumpPuff,
+            PlayerState::Land => ParticlePreset::LandPuff,
+            PlayerState::Dash => ParticlePreset::DashBurst,
+            _ => continue,
+        };
+
+        let position = match preset {
+            ParticlePreset::DashBurst => position,
+            _ => position + FEET_OFFSET,
+        };
+
+        bursts.send(ParticleBurst::new(preset, position));
+    }
+}
+
+/// Fait trembler la caméra au début d'un dash et lors des atterrissages violents
+pub fn shake_camera(
+    query: Query<(&Player, &Velocity, Option<Ref<Dash>>)>,
+    mut shakes: EventWriter<CameraShakeEvent>,
+    // vitesse de chute de la frame précédente, au moment de l'atterrissage elle est déjà a 0
+    mut fall_speed: Local<f32>,
+    mut was_grounded: Local<bool>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let (player, velocity, dash) = query.single();
+
+    if let Some(dash) = dash {
+        if dash.is_added() {
+            let direction = match dash.direction {
+                DashDirection::North => Vec2::new(0.0, 1.0),
+                DashDirection::South => Vec2::new(0.0, -1.0),
+                DashDirection::West => Vec2::new(-1.0, 0.0),
+                DashDirection::East => Vec2::new(1.0, 0.0),
+                DashDirection::NorthWest => Vec2::new(-1.0, 1.0),
+                DashDirection::NorthEast => Vec2::new(1.0, 1.0),
+                DashDirection::SouthWest => Vec2::new(-1.0, -1.0),
+                DashDirection::SouthEast => Vec2::new(1.0, -1.0),
+            };
+            shakes.send(CameraShakeEvent::new(0.3, 0.2).with_direction(direction));
+        }
+    }
+
+    if player.grounded && !*was_grounded && *fall_speed > HEAVY_LANDING_SPEED {
+        shakes.send(CameraShakeEvent::new(0.35, 0.25).with_direction(Vec2::Y));
+    }
+
+    *was_grounded = player.grounded;
+    *fall_speed = -velocity.linvel.y;
+}
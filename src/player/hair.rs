@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+
+use crate::player::{*, Direction};
+
+/// Nombre de segments de la mèche, le premier est accroché au sprite du joueur
+const HAIR_SEGMENTS: usize = 5;
+
+/// Nombre d'itérations pour résoudre les contraintes de distance entre les segments
+/// Plus la valeur est haute plus la mèche est "rigide"
+const CONSTRAINT_ITERATIONS: usize = 4;
+
+/// Durée du flash quand le dash est rechargé
+const FLASH_DURATION: f32 = 0.15;
+
+/// Position de l'attache de la mèche par rapport au centre du sprite, pour un joueur qui regarde
+/// vers la droite (le x est inversé quand il regarde a gauche)
+const HAIR_ANCHOR: Vec2 = Vec2::new(-2.0, 5.0);
+
+/// Z des segments, derrière le joueur mais devant les silhouettes de dash
+const HAIR_Z: f32 = -0.5;
+
+// couleurs de la mèche en fonction des dashs restants
+const COLOR_NO_DASH: Color = Color::rgb(0.27, 0.72, 1.0);
+const COLOR_ONE_DASH: Color = Color::rgb(0.67, 0.2, 0.2);
+const COLOR_MULTIPLE_DASHES: Color = Color::rgb(1.0, 0.43, 0.94);
+const COLOR_FLASH: Color = Color::WHITE;
+
+/// Mèche de cheveux (ou écharpe) simulée avec une chaîne de verlet
+#[derive(Component, Reflect)]
+pub struct Hair {
+    /// Distance maximale entre deux segments
+    segment_length: f32,
+    /// Gravité appliquée a chaque segment (en pixels/s²)
+    gravity: Vec2,
+    /// Force qui pousse la mèche vers l'arrière du joueur
+    drag: f32,
+    /// Conservation de la vitesse entre deux frames, entre 0 et 1
+    damping: f32,
+
+    nodes: Vec<Vec2>,
+    previous: Vec<Vec2>,
+
+    flash: f32,
+    last_dashes: u32,
+}
+
+impl Default for Hair {
+    fn default() -> Self {
+        Hair {
+            segment_length: 2.0,
+            gravity: Vec2::new(0.0, -300.0),
+            drag: 120.0,
+            damping: 0.85,
+
+            nodes: Vec::new(),
+            previous: Vec::new(),
+
+            flash: 0.0,
+            last_dashes: 0,
+        }
+    }
+}
+
+/// Segment affiché de la mèche, l'index correspond au noeud de la chaîne
+#[derive(Component)]
+pub struct HairSegment {
+    owner: Entity,
+    index: usize,
+}
+
+/// Position de l'attache dans le monde en fonction de la direction du joueur
+fn anchor_position(transform: &Transform, direction: &Direction) -> Vec2 {
+    let anchor = match direction {
+        Direction::Right => HAIR_ANCHOR,
+        Direction::Left => Vec2::new(-HAIR_ANCHOR.x, HAIR_ANCHOR.y),
+    };
+
+    transform.translation.truncate() + anchor
+}
+
+/// Couleur de la mèche en fonction du nombre de dashs restants
+fn hair_color(dashes: u32) -> Color {
+    match dashes {
+        0 => COLOR_NO_DASH,
+        1 => COLOR_ONE_DASH,
+        _ => COLOR_MULTIPLE_DASHES,
+    }
+}
+
+/// Fait spawn les segments de la mèche quand un joueur apparait
+pub fn spawn_hair(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform, &Direction, &Player), Added<Player>>,
+) {
+    for (entity, transform, direction, player) in &query {
+        let anchor = anchor_position(transform, direction);
+
+        let mut hair = Hair {
+            last_dashes: player.dashes,
+            ..Default::default()
+        };
+        hair.nodes = (0..HAIR_SEGMENTS)
+            .map(|i| anchor - Vec2::new(0.0, i as f32 * hair.segment_length))
+            .collect();
+        hair.previous = hair.nodes.clone();
+
+        for index in 0..HAIR_SEGMENTS {
+            // les segments rapetissent vers le bout de la mèche
+            let size = 4.0 - (index as f32 * 2.0 / HAIR_SEGMENTS as f32).floor();
+
+            commands.spawn((
+                Name::new("Hair segment"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: hair_color(player.dashes),
+                        custom_size: Some(Vec2::splat(size)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(hair.nodes[index].extend(HAIR_Z)),
+                    ..Default::default()
+                },
+                HairSegment {
+                    owner: entity,
+                    index,
+                },
+            ));
+        }
+
+        commands.entity(entity).insert(hair);
+    }
+}
+
+/// Simule la chaîne de verlet : le premier noeud suit l'attache, les autres subissent la gravité
+/// et sont retenus par des contraintes de distance
+pub fn simulate_hair(
+    time: Res<Time>,
+    mut query: Query<(&Transform, &Direction, &Player, &mut Hair)>,
+) {
+    let delta = time.delta_seconds();
+    if delta == 0.0 {
+        return;
+    }
+
+    for (transform, direction, player, mut hair) in &mut query {
+        let hair = &mut *hair;
+
+        // la mèche flotte vers l'arrière du joueur
+        let back = match direction {
+            Direction::Right => Vec2::new(-1.0, 0.0),
+            Direction::Left => Vec2::new(1.0, 0.0),
+        };
+        let acceleration = hair.gravity + back * hair.drag;
+
+        hair.nodes[0] = anchor_position(transform, direction);
+        hair.previous[0] = hair.nodes[0];
+
+        for i in 1..hair.nodes.len() {
+            let velocity = (hair.nodes[i] - hair.previous[i]) * hair.damping;
+            hair.previous[i] = hair.nodes[i];
+            hair.nodes[i] += velocity + acceleration * delta * delta;
+        }
+
+        for _ in 0..CONSTRAINT_ITERATIONS {
+            for i in 1..hair.nodes.len() {
+                let offset = hair.nodes[i] - hair.nodes[i - 1];
+                if offset.length() > hair.segment_length {
+                    hair.nodes[i] = hair.nodes[i - 1] + offset.normalize() * hair.segment_length;
+                }
+            }
+        }
+
+        // flash quand le dash est rechargé
+        if player.dashes > hair.last_dashes {
+            hair.flash = FLASH_DURATION;
+        }
+        hair.last_dashes = player.dashes;
+        hair.flash = (hair.flash - delta).max(0.0);
+    }
+}
+
+/// Place les sprites des segments sur les noeuds de la chaîne et met a jour leur couleur
+pub fn update_hair_segments(
+    mut commands: Commands,
+    owners: Query<(&Hair, &Player)>,
+    mut segments: Query<(Entity, &HairSegment, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, segment, mut transform, mut sprite) in &mut segments {
+        // le joueur a été despawn, on nettoie la mèche
+        let Ok((hair, player)) = owners.get(segment.owner) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        let Some(node) = hair.nodes.get(segment.index) else {
+            continue;
+        };
+
+        // on arrondit au pixel pour garder un rendu pixel art propre
+        transform.translation = node.round().extend(HAIR_Z);
+
+        sprite.color = if hair.flash > 0.0 {
+            COLOR_FLASH
+        } else {
+            hair_color(player.dashes)
+        };
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::mechanics::LaunchPlayer;
+use crate::player::*;
+
+use super::dash::DashCooldown;
+use super::movement::JumpBuffer;
+
+/// Temps pendant lequel le contrôle en l'air est réduit après une propulsion
+const LAUNCH_TIME: f32 = 0.25;
+
+/// Le joueur vient d'être propulsé par un ressort ou un bumper, strafe le laisse garder sa
+/// vitesse
+#[derive(Component)]
+pub struct Launched(pub Timer);
+
+impl Default for Launched {
+    fn default() -> Self {
+        Launched(Timer::from_seconds(LAUNCH_TIME, TimerMode::Once))
+    }
+}
+
+/// Applique la propulsion : annule le saut et le dash, recharge le dash
+pub fn launch_player(
+    mut commands: Commands,
+    mut launches: EventReader<LaunchPlayer>,
+    mut query: Query<(Entity, &mut Player, &mut Velocity)>,
+) {
+    let Some(launch) = launches.read().last() else {
+        return;
+    };
+
+    if query.is_empty() {
+        return;
+    }
+
+    let (entity, mut player, mut velocity) = query.single_mut();
+
+    velocity.linvel = launch.velocity;
+    player.dashes = player.max_dashes;
+
+    commands
+        .entity(entity)
+        .remove::<(Jump, JumpBuffer, Dash, DashCooldown)>()
+        // le dash enlève la gravité
+        .insert((
+            GravityScale(GRAVITY_SCALE),
+            PlayerState::Air,
+            Launched::default(),
+        ));
+}
+
+pub fn update_launched(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Launched)>,
+) {
+    for (entity, mut launched) in &mut query {
+        if launched.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Launched>();
+        }
+    }
+}
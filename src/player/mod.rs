@@ -7,12 +7,25 @@
 
 use bevy::prelude::*;
 
+use crate::config::GameState;
+
 mod dash;
-use dash::Dash;
+pub use dash::Dash;
 
+mod afterimage;
 mod animation;
+mod death;
+mod effects;
+mod hair;
+mod launch;
 mod movement;
+mod riding;
 mod setup;
+mod surface;
+
+pub use death::PlayerDied;
+pub use launch::Launched;
+pub use surface::{Ground, Surface};
 
 // pub car on veut y avoir accès dans les autres modules du player
 pub const AIR_FRICTION: f32 = 50.0;
@@ -23,8 +36,10 @@ pub const GRAVITY_SCALE: f32 = 0.8;
 // pour les components bevy il faut utiliser cette macro
 // doc : https://bevy-cheatbook.github.io/programming/ec.html#components
 /// Data associée au player
+// Component et Default permettent de remplir le component depuis les propriétés de Tiled
 #[allow(dead_code)]
 #[derive(Component, Reflect)]
+#[reflect(Component, Default)]
 pub struct Player {
     speed: f32,
     grounded: bool,
@@ -35,6 +50,8 @@ pub struct Player {
     dash_speed: f32,
     dash_max_time: f32,
     dash_reset_time: f32,
+    max_dashes: u32,
+    dashes: u32,
 }
 
 // le trait default permet d'instancier un objet avec des valeurs par défaut défini au compile-time
@@ -59,18 +76,29 @@ impl Default for Player {
             dash_speed: 250.0,
             dash_max_time: 0.2,
             dash_reset_time: 1.0,
+            max_dashes: 1,
+            dashes: 1,
         }
     }
 }
 
+impl Player {
+    pub fn is_grounded(&self) -> bool {
+        self.grounded
+    }
+}
+
 // les structs peuvent être des "units", des "tuples" ou des structs avec des membres
 // doc : https://doc.rust-lang.org/rust-by-example/custom_types/structs.html
 // ici la le float du Jump correspond a sa hauteur
-#[derive(Component, Reflect)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, Default)]
 struct Jump(f32);
 
-#[derive(Component, Clone, Reflect)]
-enum Direction {
+#[derive(Component, Clone, Default, Reflect)]
+#[reflect(Component, Default)]
+pub enum Direction {
+    #[default]
     Right,
     Left,
 }
@@ -79,8 +107,10 @@ enum Direction {
 // ici on enlève les warnings pour les membres non utilisés
 #[allow(dead_code)]
 // ici on dérive en plus de PartialEq et Eq pour pouvoir faire des comparaisons sur notre enum
-#[derive(Component, PartialEq, Eq, Reflect)]
+#[derive(Component, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default)]
 enum PlayerState {
+    #[default]
     Idle,
     Run,
     Dash,
@@ -95,23 +125,41 @@ pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup::setup_player).add_systems(
+        app.add_event::<PlayerDied>()
+            .init_resource::<afterimage::AfterimageSettings>()
+            .init_resource::<afterimage::AfterimagePool>()
+            .add_systems(
+                Startup,
+                (setup::setup_player, afterimage::setup_afterimages),
+            );
+        app.add_systems(
             Update,
             (
+                death::respawn_player,
                 // physics
                 movement::check_for_ground,
+                surface::update_surface,
+                surface::bounce,
+                dash::refill_dash,
                 dash::dash_cooldown,
+                launch::launch_player,
+                launch::update_launched,
                 movement::strafe,
+                movement::apply_wind,
                 movement::jump,
+                movement::drop_through,
                 movement::jump_release,
                 movement::rise,
                 dash::dash,
                 dash::dashing,
+                riding::ride_platforms,
                 movement::update_direction,
                 movement::remove_buffer,
                 movement::jump_buffer,
             )
-                .chain(),
+                .chain()
+                // le joueur est figé pendant les transitions entre les salles
+                .run_if(in_state(GameState::Playing)),
         );
         app.add_systems(
             PostUpdate,
@@ -125,8 +173,17 @@ impl Plugin for PlayerPlugin {
                 animation::update_dash_color,
                 animation::reset_dash_color,
                 animation::post_dash,
-                dash::spawn_dash_trail,
-                dash::fade_out_trail,
+                afterimage::spawn_afterimages,
+                afterimage::fade_afterimages,
+                // cheveux
+                hair::spawn_hair,
+                hair::simulate_hair,
+                hair::update_hair_segments,
+                // particules
+                effects::spawn_run_dust,
+                effects::update_run_dust,
+                effects::emit_state_particles,
+                effects::shake_camera,
             )
                 .chain(),
             // on peut déclarer plusieurs systèmes dans l'update d'un coup, on peut aussi call la
@@ -138,6 +195,8 @@ impl Plugin for PlayerPlugin {
         app.register_type::<Player>()
             .register_type::<PlayerState>()
             .register_type::<Jump>()
-            .register_type::<Direction>();
+            .register_type::<Direction>()
+            .register_type::<hair::Hair>()
+            .register_type::<Surface>();
     }
 }
@@ -7,8 +7,22 @@ use bevy_rapier2d::prelude::*;
 // dans la hiérarchie de rust, movement appartient à player
 use crate::player::{*, Direction};
 
+use super::launch::Launched;
+use super::riding::Momentum;
+use super::surface::{Ground, GroundSurface};
+use crate::mechanics::one_way::{DropThrough, OneWayPlatform, RISING_THRESHOLD};
+use crate::mechanics::PlayerWind;
+
 const BUFFER_TIME: f32 = 0.1;
 
+/// Temps d'accumulation du vent équivalent au décalage de vitesse au sol
+const GROUND_WIND_TIME: f32 = 0.1;
+/// Vitesse maximale donnée par le vent, en secondes d'accélération
+const WIND_MAX_TIME: f32 = 0.5;
+
+/// Part du contrôle en l'air gardée juste après une propulsion
+const LAUNCH_AIR_CONTROL: f32 = 0.3;
+
 #[derive(Component, Default)]
 pub struct JumpBuffer(f32);
 
@@ -23,13 +37,23 @@ pub fn strafe(
     button_inputs: Res<ButtonInput<GamepadButton>>,
     axes: Res<Axis<GamepadAxis>>,
     mut commands: Commands,
-    mut query: Query<(Entity, &mut Velocity, &Player, &PlayerState), Without<Dash>>,
+    mut query: Query<
+        (
+            Entity,
+            &mut Velocity,
+            &Player,
+            &PlayerState,
+            Option<&GroundSurface>,
+            Has<Launched>,
+        ),
+        Without<Dash>,
+    >,
 ) {
     if query.is_empty() {
         return;
     }
 
-    let (entity, mut velocity, player, state) = query.single_mut();
+    let (entity, mut velocity, player, state, surface, launched) = query.single_mut();
 
     // Mouvement qui sera appliqué au player après avoir process les inputs et son state
     let mut movement: f32 = 0.0;
@@ -79,7 +103,16 @@ pub fn strafe(
             }
         }
 
-        velocity.linvel.x = movement;
+        // le matériau du sol change l'accélération et la vitesse max (glace, boue...)
+        velocity.linvel.x = match surface {
+            Some(GroundSurface(surface)) => {
+                surface.apply(velocity.linvel.x, movement, time.delta_seconds())
+            }
+            None => movement,
+        };
+    } else if launched {
+        // après un ressort le joueur garde sa vitesse, il peut seulement l'infléchir un peu
+        velocity.linvel.x += movement / AIR_FRICTION * LAUNCH_AIR_CONTROL;
     } else {
         if movement != 0.0 {
             velocity.linvel.x += movement / AIR_FRICTION;
@@ -91,6 +124,40 @@ pub fn strafe(
     }
 }
 
+/// Le vent s'ajoute après strafe : en l'air il s'accumule avec le contrôle aérien, au sol strafe
+/// impose la vitesse a chaque frame donc le vent donne seulement un décalage constant
+pub fn apply_wind(
+    time: Res<Time>,
+    wind: Res<PlayerWind>,
+    mut query: Query<(&Player, &mut Velocity), Without<Dash>>,
+) {
+    if query.is_empty() || wind.0 == Vec2::ZERO {
+        return;
+    }
+
+    let (player, mut velocity) = query.single_mut();
+    let mut push = wind.0 * time.delta_seconds();
+
+    if player.grounded {
+        push.x = wind.0.x * GROUND_WIND_TIME;
+        velocity.linvel.x += push.x;
+    } else {
+        // le vent ne pousse plus une fois que le joueur va aussi vite que lui dans sa direction
+        let max = wind.0.abs() * WIND_MAX_TIME;
+        let along = velocity.linvel * wind.0.signum();
+        if along.x < max.x {
+            velocity.linvel.x += push.x.abs().min(max.x - along.x) * wind.0.x.signum();
+        }
+    }
+
+    // courants ascendants ou descendants, la gravité de Rapier s'applique en plus
+    let along = velocity.linvel.y * wind.0.y.signum();
+    let max = wind.0.y.abs() * WIND_MAX_TIME;
+    if wind.0.y != 0.0 && along < max {
+        velocity.linvel.y += push.y.abs().min(max - along) * wind.0.y.signum();
+    }
+}
+
 pub fn jump(
     input: Res<ButtonInput<KeyCode>>,
     mut commands: Commands,
@@ -204,13 +271,13 @@ pub fn jump_release(
 pub fn rise(
     mut commands: Commands,
     time: Res<Time>,
-    mut query: Query<(Entity, &mut Velocity, &mut Jump, &Player)>,
+    mut query: Query<(Entity, &mut Velocity, &mut Jump, &Player, Option<&Momentum>)>,
 ) {
     if query.is_empty() {
         return;
     }
 
-    let (entity, mut velocity, mut jump, player) = query.single_mut();
+    let (entity, mut velocity, mut jump, player, momentum) = query.single_mut();
 
     let mut movement = time.delta_seconds() * player.jump_force;
 
@@ -222,10 +289,61 @@ pub fn rise(
     // FIXME: i hate delta
     jump.0 += movement / 100.0;
 
-    velocity.linvel.y = movement;
+    // élan vertical d'une plateforme mobile quittée en sautant
+    velocity.linvel.y = movement + momentum.map_or(0.0, |momentum| momentum.0.y);
+}
+
+/// Bas + saut sur une plateforme a sens unique fait tomber au travers au lieu de sauter
+pub fn drop_through(
+    input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    button_inputs: Res<ButtonInput<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Player, Option<&Ground>), Without<DropThrough>>,
+    platforms: Query<(), With<OneWayPlatform>>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let (entity, mut player, ground) = query.single_mut();
+
+    let on_platform = ground.is_some_and(|ground| platforms.contains(ground.entity));
+    if !player.grounded || !on_platform {
+        return;
+    }
+
+    let mut drop = input.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown])
+        && input.any_just_pressed([KeyCode::KeyW, KeyCode::ArrowUp, KeyCode::Space]);
+
+    for gamepad in gamepads.iter() {
+        let left_stick_y = axes
+            .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+            .unwrap();
+        let down = left_stick_y < -JOYSTICK_THRESHOLD
+            || button_inputs.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadDown));
+
+        if down && button_inputs.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::South))
+        {
+            drop = true;
+        }
+    }
+
+    if drop {
+        player.grounded = false;
+        // le saut a déjà été lancé par le système jump, on l'annule
+        commands
+            .entity(entity)
+            .insert((DropThrough::default(), PlayerState::Air))
+            .remove::<(Jump, JumpBuffer, Ground)>();
+    }
 }
 
-pub fn update_direction(mut commands: Commands, query: Query<(Entity, &Velocity), Without<Dash>>) {
+pub fn update_direction(
+    mut commands: Commands,
+    query: Query<(Entity, &Velocity), (With<Player>, Without<Dash>)>,
+) {
     if query.is_empty() {
         return;
     }
@@ -242,29 +360,45 @@ pub fn update_direction(mut commands: Commands, query: Query<(Entity, &Velocity)
 // doc raycast :
 // https://rapier.rs/docs/user_guides/bevy_plugin/scene_queries/#query-filters
 pub fn check_for_ground(
-    mut query: Query<(Entity, &mut Player, &Transform)>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Player, &Transform, &Velocity, Has<DropThrough>)>,
+    platforms: Query<(), With<OneWayPlatform>>,
     rapier_context: Res<RapierContext>,
 ) {
     if query.is_empty() {
         return;
     }
 
-    let (entity, mut player, transform) = query.single_mut();
+    let (entity, mut player, transform, velocity, dropping) = query.single_mut();
 
     let ray_pos = Vec2::new(transform.translation.x, transform.translation.y);
     let ray_dir = Vec2::new(0.0, -1.0);
     let max_toi = 8.0; // INFO: should be the height of the player (collider's halfsize * 2)
     let solid = true;
-    let filter = QueryFilter::exclude_dynamic()
+    let mut filter = QueryFilter::exclude_dynamic()
         .exclude_sensors()
         .exclude_rigid_body(entity);
 
-    if rapier_context
+    // les plateformes a sens unique ne comptent pas quand on passe au travers
+    let not_platform = |entity: Entity| !platforms.contains(entity);
+    if dropping || velocity.linvel.y > RISING_THRESHOLD {
+        filter = filter.predicate(&not_platform);
+    }
+
+    let hit = rapier_context
         .cast_ray(ray_pos, ray_dir, max_toi, solid, filter)
-        .is_some()
-    {
+        // le rayon part de l'intérieur de la plateforme, le joueur n'est pas encore dessus
+        .filter(|(ground, toi)| *toi > 0.0 || !platforms.contains(*ground));
+
+    if let Some((ground, toi)) = hit {
         player.grounded = true;
+        // le collider touché sert a retrouver le matériau du sol
+        commands.entity(entity).insert(Ground {
+            entity: ground,
+            point: ray_pos + ray_dir * toi,
+        });
     } else {
         player.grounded = false;
+        commands.entity(entity).remove::<Ground>();
     }
 }
@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::mechanics::MovingPlatform;
+use crate::player::*;
+
+use super::surface::Ground;
+
+/// Plateforme mobile sur laquelle se tient le joueur
+#[derive(Component)]
+pub struct Riding {
+    pub platform: Entity,
+    pub velocity: Vec2,
+}
+
+/// Vitesse d'une plateforme gardée par le joueur quand il saute ou dash depuis celle-ci, elle
+/// s'ajoute aux vitesses imposées par le saut et le dash en s'amortissant jusqu'a ce qu'il
+/// retouche le sol
+#[derive(Component, Clone, Copy)]
+pub struct Momentum(pub Vec2);
+
+/// Amortissement de l'élan par seconde
+const MOMENTUM_DAMPING: f32 = 6.0;
+
+/// En dessous de cette vitesse (px/s) l'élan est retiré
+const MOMENTUM_MIN_SPEED: f32 = 1.0;
+
+/// Déplace le joueur avec la plateforme sous ses pieds et lui donne l'élan de la plateforme
+/// quand il la quitte en sautant ou en dashant
+pub fn ride_platforms(
+    mut commands: Commands,
+    time: Res<Time>,
+    platforms: Query<&MovingPlatform>,
+    mut query: Query<(
+        Entity,
+        &Player,
+        &mut Transform,
+        &mut Velocity,
+        Option<&Ground>,
+        Option<&Riding>,
+        Option<&mut Momentum>,
+        Has<Jump>,
+        Has<Dash>,
+    )>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let (entity, player, mut transform, mut velocity, ground, riding, momentum, jumping, dashing) =
+        query.single_mut();
+
+    let platform = ground.and_then(|ground| {
+        platforms
+            .get(ground.entity)
+            .ok()
+            .map(|platform| (ground.entity, platform.velocity()))
+    });
+
+    match (platform, riding) {
+        (Some((platform, platform_velocity)), _) => {
+            // la plateforme avance pendant le pas de physique, on avance le joueur d'autant, le
+            // contact de Rapier s'occupe déjà de le pousser quand elle monte
+            transform.translation.x += platform_velocity.x * time.delta_seconds();
+
+            // quand elle descend le joueur la suit au lieu de tomber et rebondir dessus
+            if platform_velocity.y < 0.0 && !jumping && velocity.linvel.y > platform_velocity.y {
+                velocity.linvel.y = platform_velocity.y;
+            }
+
+            // l'élan d'une ancienne plateforme ne doit pas s'appliquer au prochain saut
+            if riding.map_or(true, |riding| riding.platform != platform) {
+                commands.entity(entity).remove::<Momentum>();
+            }
+
+            commands.entity(entity).insert(Riding {
+                platform,
+                velocity: platform_velocity,
+            });
+        }
+        (None, Some(riding)) => {
+            commands.entity(entity).remove::<Riding>();
+
+            if jumping || dashing {
+                velocity.linvel.x += riding.velocity.x;
+                commands.entity(entity).insert(Momentum(riding.velocity));
+            }
+        }
+        (None, None) if player.grounded => {
+            commands.entity(entity).remove::<Momentum>();
+        }
+        (None, None) => {
+            // le saut et le dash réimposent leur vitesse à chaque frame, l'élan s'estompe pour
+            // ne pas s'ajouter tel quel pendant toute leur durée
+            if let Some(mut momentum) = momentum {
+                momentum.0 *= (-MOMENTUM_DAMPING * time.delta_seconds()).exp();
+
+                if momentum.0.length() < MOMENTUM_MIN_SPEED {
+                    commands.entity(entity).remove::<Momentum>();
+                }
+            }
+        }
+    }
+}
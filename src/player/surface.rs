@@ -0,0 +1,153 @@
+// matériaux de surface : une tuile (ou un objet) avec un component Surface change la façon dont
+// le joueur se déplace dessus
+//
+// dans Tiled on rajoute aux tuiles du tileset une propriété de classe "Surface" (voir
+// tile/properties.rs), par exemple pour de la glace :
+//   Surface { acceleration: 2.0, deceleration: 0.5 }
+// pour de la boue : Surface { max_speed: 0.5 }, pour un trampoline : Surface { bounce: 300.0 }
+
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::player::Player;
+use crate::tile::properties::ReflectFromTiled;
+
+/// Matériau d'une surface, les valeurs par défaut correspondent au sol normal
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component, Default, FromTiled)]
+pub struct Surface {
+    /// Vitesse a laquelle le joueur atteint sa vitesse max (par seconde), 0 pour instantané
+    pub acceleration: f32,
+    /// Vitesse a laquelle le joueur s'arrête (par seconde), 0 pour instantané
+    pub deceleration: f32,
+    /// Multiplicateur de la vitesse max au sol
+    pub max_speed: f32,
+    /// Vitesse verticale donnée au joueur quand il atterrit dessus, 0 pour ne pas rebondir
+    pub bounce: f32,
+}
+
+impl Default for Surface {
+    fn default() -> Self {
+        Surface {
+            acceleration: 0.0,
+            deceleration: 0.0,
+            max_speed: 1.0,
+            bounce: 0.0,
+        }
+    }
+}
+
+impl Surface {
+    /// Nouvelle vitesse horizontale au sol a partir de la vitesse voulue par les inputs
+    pub fn apply(&self, current: f32, target: f32, delta: f32) -> f32 {
+        let target = target * self.max_speed;
+
+        // on accélère si on va plus vite dans le même sens, sinon on freine
+        let accelerating = target != 0.0 && (current == 0.0 || target.signum() == current.signum());
+        let rate = if accelerating {
+            self.acceleration
+        } else {
+            self.deceleration
+        };
+
+        if rate <= 0.0 {
+            return target;
+        }
+
+        current + (target - current) * (1.0 - (-rate * delta).exp())
+    }
+}
+
+/// Surface sous le joueur, absent quand il est en l'air
+#[derive(Component, Clone, Copy)]
+pub struct GroundSurface(pub Surface);
+
+/// Collider touché par le rayon de check_for_ground
+#[derive(Component)]
+pub struct Ground {
+    pub entity: Entity,
+    pub point: Vec2,
+}
+
+/// Retrouve la surface sous le joueur : directement sur le collider touché, ou sur la tuile au
+/// point touché si le collider est celui d'un calque de tuiles
+pub fn update_surface(
+    mut commands: Commands,
+    player: Query<(Entity, Option<&Ground>, Option<&GroundSurface>), With<Player>>,
+    surfaces: Query<&Surface>,
+    parents: Query<&Parent>,
+    layers: Query<(
+        &TileStorage,
+        &TilemapSize,
+        &TilemapGridSize,
+        &TilemapType,
+        &GlobalTransform,
+    )>,
+) {
+    if player.is_empty() {
+        return;
+    }
+
+    let (entity, ground, current) = player.single();
+
+    let surface = ground.and_then(|ground| {
+        if let Ok(surface) = surfaces.get(ground.entity) {
+            return Some(*surface);
+        }
+
+        let layer = parents.get(ground.entity).ok()?.get();
+        let (storage, size, grid_size, map_type, transform) = layers.get(layer).ok()?;
+
+        // le point touché est sur le bord de la tuile, on regarde un peu en dessous
+        let point = ground.point - Vec2::new(0.0, grid_size.y / 4.0);
+        let local = transform
+            .affine()
+            .inverse()
+            .transform_point3(point.extend(0.0))
+            .truncate();
+        let tile_pos = TilePos::from_world_pos(&local, size, grid_size, map_type)?;
+
+        surfaces.get(storage.get(&tile_pos)?).ok().copied()
+    });
+
+    match (surface, current) {
+        (Some(surface), Some(GroundSurface(current))) if surface == *current => {}
+        (Some(surface), _) => {
+            commands.entity(entity).insert(GroundSurface(surface));
+        }
+        (None, Some(_)) if ground.is_none() => {
+            commands.entity(entity).remove::<GroundSurface>();
+        }
+        // sol sans matériau
+        (None, Some(_)) => {
+            commands
+                .entity(entity)
+                .insert(GroundSurface(Surface::default()));
+        }
+        (None, None) => {}
+    }
+}
+
+/// Fait rebondir le joueur quand il atterrit sur une surface qui rebondit
+pub fn bounce(
+    mut was_grounded: Local<bool>,
+    mut query: Query<(&Player, &mut Velocity, Option<&GroundSurface>)>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let (player, mut velocity, surface) = query.single_mut();
+    let grounded = player.is_grounded();
+
+    if grounded && !*was_grounded && velocity.linvel.y <= 0.0 {
+        if let Some(GroundSurface(surface)) = surface {
+            if surface.bounce > 0.0 {
+                velocity.linvel.y = surface.bounce;
+            }
+        }
+    }
+
+    *was_grounded = grounded;
+}
@@ -0,0 +1,258 @@
+// découpage des niveaux en salles, chaque salle est un rectangle dans un calque d'objets de Tiled
+// (classe "room" ou calque "rooms"), quand le joueur sort de la salle active le gameplay est figé
+// le temps que la caméra glisse vers la nouvelle salle
+
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use bevy_rapier2d::prelude::*;
+
+use crate::camera::{clamp_to_bounds, CameraBounds, CameraSystem, PlayerCamera};
+use crate::config::GameState;
+use crate::player::{Player, PlayerDied};
+use crate::tile::objects::{object_rect, TiledObject};
+
+/// Durée du glissement de la caméra entre deux salles
+const TRANSITION_DURATION: f32 = 0.4;
+
+/// Distance sous le bas de la salle a partir de laquelle le joueur meurt
+const FALL_MARGIN: f32 = 16.0;
+
+pub struct RoomPlugin;
+
+impl Plugin for RoomPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveRoom>()
+            .init_resource::<Checkpoint>()
+            .add_systems(
+                Update,
+                (
+                    setup_rooms,
+                    detect_room_change.run_if(in_state(GameState::Playing)),
+                    update_camera_bounds,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    (assign_rooms, pause_inactive_rooms)
+                        .chain()
+                        .after(TransformSystem::TransformPropagate),
+                    scroll_camera
+                        .run_if(in_state(GameState::Frozen))
+                        .in_set(CameraSystem::Follow),
+                ),
+            );
+    }
+}
+
+/// Salle du niveau, centrée sur l'entité
+#[derive(Component)]
+pub struct Room {
+    pub size: Vec2,
+}
+
+/// Point de réapparition d'une salle
+#[derive(Component)]
+pub struct RoomCheckpoint;
+
+/// Salle a laquelle appartient une entité, elle est mise en pause quand la salle n'est pas active
+#[derive(Component)]
+pub struct InRoom(pub Entity);
+
+/// Marque une entité dont la salle n'est pas active, garde sa visibilité d'origine
+#[derive(Component)]
+pub struct RoomPaused(Visibility);
+
+/// Salle dans laquelle se trouve le joueur
+#[derive(Resource, Default)]
+pub struct ActiveRoom(pub Option<Entity>);
+
+/// Position où le joueur réapparait quand il meurt, par défaut son point de spawn
+#[derive(Resource, Default)]
+pub struct Checkpoint(pub Vec2);
+
+/// Transition de caméra en cours entre deux salles
+#[derive(Resource)]
+struct RoomTransition {
+    from: Vec2,
+    to: Vec2,
+    elapsed: f32,
+}
+
+fn setup_rooms(mut commands: Commands, query: Query<(Entity, &TiledObject), Added<TiledObject>>) {
+    for (entity, object) in &query {
+        if object.is("room") {
+            commands.entity(entity).insert(Room { size: object.size });
+        } else if object.is("checkpoint") {
+            commands.entity(entity).insert(RoomCheckpoint);
+        }
+    }
+}
+
+/// Range les objets de la map dans la salle qui les contient, on attend que le GlobalTransform
+/// soit propagé pour avoir leur vraie position
+fn assign_rooms(
+    mut commands: Commands,
+    rooms: Query<(Entity, &TiledObject, &GlobalTransform), With<Room>>,
+    objects: Query<(Entity, &GlobalTransform), (With<TiledObject>, Without<Room>, Without<InRoom>)>,
+) {
+    for (entity, transform) in &objects {
+        let position = transform.translation().truncate();
+
+        let room = rooms
+            .iter()
+            .find(|(_, room, room_transform)| object_rect(room, room_transform).contains(position));
+
+        if let Some((room, _, _)) = room {
+            commands.entity(entity).insert(InRoom(room));
+        }
+    }
+}
+
+/// Change de salle quand le joueur sort de la salle active, ou le tue s'il tombe hors des salles
+fn detect_room_change(
+    mut commands: Commands,
+    mut active_room: ResMut<ActiveRoom>,
+    mut checkpoint: ResMut<Checkpoint>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut deaths: EventWriter<PlayerDied>,
+    player: Query<&Transform, With<Player>>,
+    rooms: Query<(Entity, &TiledObject, &GlobalTransform), With<Room>>,
+    checkpoints: Query<(&GlobalTransform, &InRoom), With<RoomCheckpoint>>,
+    mut camera: Query<
+        (&mut Transform, &OrthographicProjection),
+        (With<PlayerCamera>, Without<Player>),
+    >,
+) {
+    if player.is_empty() || camera.is_empty() {
+        return;
+    }
+
+    let player_position = player.single().translation.truncate();
+
+    // la salle active a pu être despawn (changement de niveau)
+    let current = active_room
+        .0
+        .and_then(|room| rooms.get(room).ok())
+        .map(|(entity, room, transform)| (entity, object_rect(room, transform)));
+
+    if let Some((_, rect)) = current {
+        if rect.contains(player_position) {
+            return;
+        }
+    }
+
+    let Some((next_room, next_object, next_transform)) = rooms
+        .iter()
+        .find(|(_, room, transform)| object_rect(room, transform).contains(player_position))
+    else {
+        if let Some((_, rect)) = current {
+            if player_position.y < rect.min.y - FALL_MARGIN {
+                deaths.send(PlayerDied);
+            }
+        }
+        return;
+    };
+    let next_rect = object_rect(next_object, next_transform);
+
+    // le checkpoint de la salle, ou a défaut l'endroit par lequel le joueur est entré
+    checkpoint.0 = checkpoints
+        .iter()
+        .find(|(_, in_room)| in_room.0 == next_room)
+        .map(|(transform, _)| transform.translation().truncate())
+        .unwrap_or(player_position);
+
+    let (mut camera_transform, projection) = camera.single_mut();
+    let from = camera_transform.translation.truncate();
+    let to = clamp_to_bounds(next_rect, player_position, projection.area.half_size());
+
+    if current.is_some() {
+        commands.insert_resource(RoomTransition {
+            from,
+            to,
+            elapsed: 0.0,
+        });
+        next_state.set(GameState::Frozen);
+    } else {
+        // la première salle est activée directement, sans transition
+        camera_transform.translation.x = to.x;
+        camera_transform.translation.y = to.y;
+    }
+
+    active_room.0 = Some(next_room);
+}
+
+/// La caméra reste dans la salle active
+fn update_camera_bounds(
+    active_room: Res<ActiveRoom>,
+    mut bounds: ResMut<CameraBounds>,
+    rooms: Query<(&TiledObject, &GlobalTransform), With<Room>>,
+) {
+    let room = active_room
+        .0
+        .and_then(|room| rooms.get(room).ok())
+        .map(|(room, transform)| object_rect(room, transform));
+
+    if bounds.room != room {
+        bounds.room = room;
+    }
+}
+
+/// Fait glisser la caméra vers la nouvelle salle puis relance le gameplay
+fn scroll_camera(
+    mut commands: Commands,
+    time: Res<Time>,
+    transition: Option<ResMut<RoomTransition>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut camera: Query<&mut Transform, With<PlayerCamera>>,
+) {
+    let Some(mut transition) = transition else {
+        return;
+    };
+
+    transition.elapsed += time.delta_seconds();
+    let t = (transition.elapsed / TRANSITION_DURATION).clamp(0.0, 1.0);
+    // smoothstep pour adoucir le début et la fin du mouvement
+    let t = t * t * (3.0 - 2.0 * t);
+
+    for mut transform in &mut camera {
+        let position = transition.from.lerp(transition.to, t);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+
+    if transition.elapsed >= TRANSITION_DURATION {
+        commands.remove_resource::<RoomTransition>();
+        next_state.set(GameState::Playing);
+    }
+}
+
+/// Met en pause les entités des salles inactives : cachées et sans collisions
+fn pause_inactive_rooms(
+    mut commands: Commands,
+    active_room: Res<ActiveRoom>,
+    mut query: Query<(Entity, &InRoom, &mut Visibility, Option<&RoomPaused>)>,
+) {
+    for (entity, in_room, mut visibility, paused) in &mut query {
+        let inactive = active_room.0.is_some_and(|room| room != in_room.0);
+
+        match (inactive, paused) {
+            (true, None) => {
+                commands.entity(entity).insert((
+                    RoomPaused(*visibility),
+                    ColliderDisabled,
+                    RigidBodyDisabled,
+                ));
+                *visibility = Visibility::Hidden;
+            }
+            (false, Some(RoomPaused(previous))) => {
+                *visibility = *previous;
+                commands
+                    .entity(entity)
+                    .remove::<(RoomPaused, ColliderDisabled, RigidBodyDisabled)>();
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,54 @@
+// animations des tuiles définies dans les tilesets de Tiled (eau, torches...), chaque frame a sa
+// propre durée donc on ne peut pas utiliser AnimatedTile de bevy_ecs_tilemap qui a une vitesse
+// fixe, on change directement le TileTextureIndex des tuiles
+
+use bevy::prelude::*;
+use bevy_ecs_tilemap::tiles::TileTextureIndex;
+
+/// Frames d'une tuile animée : index dans la texture du calque et durée en secondes
+#[derive(Component, Clone)]
+pub struct TileAnimation {
+    frames: Vec<(u32, f32)>,
+    duration: f32,
+}
+
+impl TileAnimation {
+    /// None si l'animation n'a pas de frames ou dure 0 seconde
+    pub fn new(frames: impl IntoIterator<Item = (u32, f32)>) -> Option<TileAnimation> {
+        let frames: Vec<(u32, f32)> = frames.into_iter().collect();
+        let duration = frames.iter().map(|(_, duration)| duration).sum();
+
+        if frames.is_empty() || duration <= 0.0 {
+            return None;
+        }
+
+        Some(TileAnimation { frames, duration })
+    }
+
+    fn frame_at(&self, time: f64) -> u32 {
+        let mut time = (time % self.duration as f64) as f32;
+
+        for (index, duration) in &self.frames {
+            if time < *duration {
+                return *index;
+            }
+            time -= duration;
+        }
+
+        self.frames[self.frames.len() - 1].0
+    }
+}
+
+/// Toutes les tuiles sont animées a partir du temps global, les tuiles identiques restent donc
+/// synchronisées, même après un rechargement de la map
+pub fn animate_tiles(time: Res<Time>, mut query: Query<(&TileAnimation, &mut TileTextureIndex)>) {
+    let elapsed = time.elapsed_seconds_f64();
+
+    for (animation, mut texture_index) in &mut query {
+        let frame = animation.frame_at(elapsed);
+
+        if texture_index.0 != frame {
+            texture_index.0 = frame;
+        }
+    }
+}
@@ -10,27 +10,49 @@
 //
 // Functional limitations:
 //   * When the 'atlas' feature is enabled tilesets using a collection of images will be skipped.
-//   * Only finite tile layers are loaded. Infinite tile layers and object layers will be skipped.
+//   * External tilesets (.tsx) and templates (.tx) are resolved relative to the file referencing
+//     them and are hot-reloaded along with the map.
+//   * Tile animations of the tilesets are played by changing the texture index of the tiles.
+//   * Class properties of tiles, objects and layers are inserted as reflected components, see
+//     properties.rs.
+//   * Image layers are spawned as sprites, layers with a parallax factor follow the camera, see
+//     parallax.rs.
+//   * Infinite tile layers are loaded chunk by chunk into a single tilemap covering every chunk.
+//   * Tile layers get a `TiledLayer` component holding their properties, gameplay code can
+//     react to it to add its own components.
+//   * Object layers are spawned as generic `TiledObject` entities, see objects.rs.
+//   * Tiles with the `isSolid` property get a collider, spawned as a child of their layer. Tiles
+//     with the `isOneWay` property get their own collider, marked with `TileColliders::one_way`
+//     so gameplay code can turn it into a one-way platform. Layers with a parallax factor get no
+//     colliders.
+//   * On hot reload every layer, collider and object of the map is despawned and spawned again.
 
 use std::io::{Cursor, ErrorKind};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use bevy::{
-    asset::{io::Reader, AssetLoader, AssetPath, AsyncReadExt},
+    asset::{io::Reader, AssetLoader, AssetPath, AsyncReadExt, ReadAssetBytesError},
     log,
+    math::{IVec2, Vec2},
     prelude::{
-        Added, Asset, AssetApp, AssetEvent, AssetId, Assets, Bundle, Commands, Component,
-        DespawnRecursiveExt, Entity, EventReader, GlobalTransform, Handle, Image, Plugin, Query,
-        Res, Transform, Update,
+        Added, Asset, AssetApp, AssetEvent, AssetId, Assets, BuildChildren, Bundle, Color,
+        Commands, Component, DespawnRecursiveExt, Entity, EventReader, GlobalTransform, Handle,
+        Image, Name, Plugin, Query, Res, SpatialBundle, Transform, TransformBundle, Update,
     },
     reflect::TypePath,
     utils::{BoxedFuture, HashMap},
 };
 use bevy_ecs_tilemap::prelude::*;
+use bevy_rapier2d::prelude::{Collider, RigidBody};
 
 use thiserror::Error;
 
+use super::animation::TileAnimation;
+use super::objects;
+use super::parallax::{self, Parallax};
+use super::properties;
+
 #[derive(Default)]
 pub struct TiledMapPlugin;
 
@@ -48,15 +70,58 @@ pub struct TiledMap {
 
     pub tilemap_textures: HashMap<usize, TilemapTexture>,
 
+    // The image of each image layer, by layer index.
+    pub image_layer_textures: HashMap<usize, Handle<Image>>,
+
     // The offset into the tileset_images for each tile id within each tileset.
     #[cfg(not(feature = "atlas"))]
     pub tile_image_offsets: HashMap<(usize, tiled::TileId), u32>,
 }
 
-// Stores a list of tiled layers.
+impl TiledMap {
+    // Index of a tile of a tileset in the texture of the layers using this tileset.
+    fn texture_index(
+        &self,
+        tilemap_texture: &TilemapTexture,
+        tileset_index: usize,
+        tile_id: tiled::TileId,
+    ) -> u32 {
+        match tilemap_texture {
+            TilemapTexture::Single(_) => tile_id,
+            #[cfg(not(feature = "atlas"))]
+            TilemapTexture::Vector(_) => *self
+                .tile_image_offsets
+                .get(&(tileset_index, tile_id))
+                .expect(
+                "The offset into to image vector should have been saved during the initial load.",
+            ),
+            #[cfg(not(feature = "atlas"))]
+            _ => unreachable!(),
+        }
+    }
+}
+
+// Stores the entities spawned for each tiled layer, a tile layer using several tilesets gets one
+// entity per tileset.
 #[derive(Component, Default)]
 pub struct TiledLayersStorage {
-    pub storage: HashMap<u32, Entity>,
+    pub storage: HashMap<u32, Vec<Entity>>,
+}
+
+// Tile layer as found in the map, with its properties so gameplay code can add its own
+// components to the layer.
+#[derive(Component, Clone, Debug)]
+pub struct TiledLayer {
+    pub index: u32,
+    pub opacity: f32,
+    pub properties: tiled::Properties,
+}
+
+// Compound collider of a chunk of solid tiles, spawned as a child of its tile layer. One-way
+// tiles get their own colliders so they can be told apart.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct TileColliders {
+    pub one_way: bool,
 }
 
 #[derive(Default, Bundle)]
@@ -68,15 +133,28 @@ pub struct TiledMapBundle {
     pub render_settings: TilemapRenderSettings,
 }
 
+// Serves the TMX bytes and the external files (tilesets, templates) it references. Tiled reads
+// files synchronously so the external files are read beforehand through Bevy's asset reader.
+#[derive(Clone)]
 struct BytesResourceReader {
-    bytes: Arc<[u8]>,
+    files: HashMap<PathBuf, Arc<[u8]>>,
 }
 
 impl BytesResourceReader {
-    fn new(bytes: &[u8]) -> Self {
-        Self {
-            bytes: Arc::from(bytes),
-        }
+    fn new(path: &Path, bytes: &[u8]) -> Self {
+        let mut reader = Self {
+            files: HashMap::default(),
+        };
+        reader.insert(path.to_path_buf(), bytes);
+        reader
+    }
+
+    fn insert(&mut self, path: PathBuf, bytes: &[u8]) {
+        self.files.insert(path, Arc::from(bytes));
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.files.contains_key(path)
     }
 }
 
@@ -84,9 +162,14 @@ impl tiled::ResourceReader for BytesResourceReader {
     type Resource = Cursor<Arc<[u8]>>;
     type Error = std::io::Error;
 
-    fn read_from(&mut self, _path: &Path) -> std::result::Result<Self::Resource, Self::Error> {
-        // In this case, the path is ignored because the byte data is already provided.
-        Ok(Cursor::new(self.bytes.clone()))
+    fn read_from(&mut self, path: &Path) -> std::result::Result<Self::Resource, Self::Error> {
+        match self.files.get(path) {
+            Some(bytes) => Ok(Cursor::new(bytes.clone())),
+            None => Err(std::io::Error::new(
+                ErrorKind::NotFound,
+                format!("{} hasn't been read yet", path.display()),
+            )),
+        }
     }
 }
 
@@ -97,6 +180,9 @@ pub enum TiledAssetLoaderError {
     /// An [IO](std::io) Error
     #[error("Could not load Tiled file: {0}")]
     Io(#[from] std::io::Error),
+    /// An external tileset or template couldn't be read
+    #[error("Could not read file referenced by the Tiled map: {0}")]
+    Dependency(#[from] ReadAssetBytesError),
 }
 
 impl AssetLoader for TiledLoader {
@@ -114,13 +200,36 @@ impl AssetLoader for TiledLoader {
             let mut bytes = Vec::new();
             reader.read_to_end(&mut bytes).await?;
 
-            let mut loader = tiled::Loader::with_cache_and_reader(
-                tiled::DefaultResourceCache::new(),
-                BytesResourceReader::new(&bytes),
-            );
-            let map = loader.load_tmx_map(load_context.path()).map_err(|e| {
-                std::io::Error::new(ErrorKind::Other, format!("Could not load TMX map: {e}"))
-            })?;
+            let map_path = load_context.path().to_path_buf();
+            let mut resource_reader = BytesResourceReader::new(&map_path, &bytes);
+
+            // Every time Tiled asks for a file we don't have yet (an external tileset or a
+            // template), read it and parse the map again. Reading it through the load context
+            // registers it as a dependency, so editing it hot-reloads the map.
+            let map = loop {
+                let mut loader = tiled::Loader::with_cache_and_reader(
+                    tiled::DefaultResourceCache::new(),
+                    resource_reader.clone(),
+                );
+
+                match loader.load_tmx_map(&map_path) {
+                    Ok(map) => break map,
+                    Err(tiled::Error::ResourceLoadingError { path, .. })
+                        if !resource_reader.contains(&path) =>
+                    {
+                        log::info!("Loading external Tiled file {}", path.display());
+                        let dependency = load_context.read_asset_bytes(path.clone()).await?;
+                        resource_reader.insert(path, &dependency);
+                    }
+                    Err(e) => {
+                        return Err(std::io::Error::new(
+                            ErrorKind::Other,
+                            format!("Could not load TMX map: {e}"),
+                        )
+                        .into())
+                    }
+                }
+            };
 
             let mut tilemap_textures = HashMap::default();
             #[cfg(not(feature = "atlas"))]
@@ -178,9 +287,29 @@ impl AssetLoader for TiledLoader {
                 tilemap_textures.insert(tileset_index, tilemap_texture);
             }
 
+            let mut image_layer_textures = HashMap::default();
+            for (layer_index, layer) in map.layers().enumerate() {
+                let tiled::LayerType::Image(image_layer) = layer.layer_type() else {
+                    continue;
+                };
+                let Some(img) = &image_layer.image else {
+                    continue;
+                };
+
+                let tmx_dir = load_context
+                    .path()
+                    .parent()
+                    .expect("The asset load context was empty.");
+                let asset_path = AssetPath::from(tmx_dir.join(&img.source));
+                let texture: Handle<Image> = load_context.load(asset_path);
+
+                image_layer_textures.insert(layer_index, texture);
+            }
+
             let asset_map = TiledMap {
                 map,
                 tilemap_textures,
+                image_layer_textures,
                 #[cfg(not(feature = "atlas"))]
                 tile_image_offsets,
             };
@@ -196,12 +325,153 @@ impl AssetLoader for TiledLoader {
     }
 }
 
+/// Despawns every layer of a map along with its tiles, tiles aren't children of their layer so
+/// they have to be removed through the layer's tile storage.
+pub fn despawn_layers(
+    commands: &mut Commands,
+    layer_storage: &TiledLayersStorage,
+    tile_storage_query: &Query<(Entity, &TileStorage)>,
+) {
+    for layer_entity in layer_storage.storage.values().flatten() {
+        if let Ok((_, layer_tile_storage)) = tile_storage_query.get(*layer_entity) {
+            for tile in layer_tile_storage.iter().flatten() {
+                commands.entity(*tile).despawn_recursive()
+            }
+        }
+        commands.entity(*layer_entity).despawn_recursive();
+    }
+}
+
+/// Tile property marking tiles the player collides with.
+const SOLID_PROPERTY: &str = "isSolid";
+
+/// Tile property marking tiles the player can jump through from below.
+const ONE_WAY_PROPERTY: &str = "isOneWay";
+
+fn has_bool_property(tile: &tiled::LayerTile, property: &str) -> bool {
+    tile.get_tile().is_some_and(|tile| {
+        matches!(
+            tile.properties.get(property),
+            Some(tiled::PropertyValue::BoolValue(true))
+        )
+    })
+}
+
+/// Collider covering a tile, positioned relative to its layer.
+fn tile_collider(
+    tile_pos: &TilePos,
+    grid_size: &TilemapGridSize,
+    map_type: &TilemapType,
+) -> (Vec2, f32, Collider) {
+    let center = tile_pos.center_in_world(grid_size, map_type);
+
+    (
+        center,
+        0.0,
+        Collider::cuboid(grid_size.x / 2.0, grid_size.y / 2.0),
+    )
+}
+
+/// Size of the chunks of infinite layers, finite layers are split the same way for colliders.
+const CHUNK_SIZE: IVec2 = IVec2::new(
+    tiled::ChunkData::WIDTH as i32,
+    tiled::ChunkData::HEIGHT as i32,
+);
+
+/// Area covered by a tile layer, in Tiled tile coordinates.
+struct LayerBounds {
+    /// Top left tile, negative when an infinite layer extends above or left of the map origin.
+    origin: IVec2,
+    size: TilemapSize,
+}
+
+fn layer_bounds(map: &tiled::Map, tile_layer: &tiled::TileLayer) -> Option<LayerBounds> {
+    match tile_layer {
+        tiled::TileLayer::Finite(_) => Some(LayerBounds {
+            origin: IVec2::ZERO,
+            size: TilemapSize {
+                x: map.width,
+                y: map.height,
+            },
+        }),
+        tiled::TileLayer::Infinite(layer_data) => {
+            let (min, max) = layer_data
+                .chunks()
+                .map(|((x, y), _)| IVec2::new(x, y))
+                .fold(None, |bounds, chunk| match bounds {
+                    None => Some((chunk, chunk)),
+                    Some((min, max)) => Some((chunk.min(min), chunk.max(max))),
+                })?;
+            let size = (max - min + IVec2::ONE) * CHUNK_SIZE;
+
+            Some(LayerBounds {
+                origin: min * CHUNK_SIZE,
+                size: TilemapSize {
+                    x: size.x as u32,
+                    y: size.y as u32,
+                },
+            })
+        }
+    }
+}
+
+/// Coordinates of every tile of a layer that may hold a tile, chunk by chunk for infinite layers.
+fn layer_tile_coords(tile_layer: &tiled::TileLayer, bounds: &LayerBounds) -> Vec<IVec2> {
+    match tile_layer {
+        tiled::TileLayer::Finite(_) => (0..bounds.size.y as i32)
+            .flat_map(|y| (0..bounds.size.x as i32).map(move |x| IVec2::new(x, y)))
+            .map(|coords| bounds.origin + coords)
+            .collect(),
+        tiled::TileLayer::Infinite(layer_data) => layer_data
+            .chunks()
+            .flat_map(|((x, y), _)| {
+                let chunk_origin = IVec2::new(x, y) * CHUNK_SIZE;
+                (0..CHUNK_SIZE.y).flat_map(move |y| {
+                    (0..CHUNK_SIZE.x).map(move |x| chunk_origin + IVec2::new(x, y))
+                })
+            })
+            .collect(),
+    }
+}
+
+fn get_tile_data<'a>(
+    tile_layer: &'a tiled::TileLayer,
+    x: i32,
+    y: i32,
+) -> Option<&'a tiled::LayerTileData> {
+    match tile_layer {
+        tiled::TileLayer::Finite(layer_data) => layer_data.get_tile_data(x, y),
+        tiled::TileLayer::Infinite(layer_data) => layer_data.get_tile_data(x, y),
+    }
+}
+
+/// Places an infinite layer so its tiles line up with a finite layer of the same map: the tile
+/// at Tiled coordinates (0, 0) is at the top left of the map, whatever the chunks it spans.
+fn infinite_layer_transform(
+    map: &tiled::Map,
+    bounds: &LayerBounds,
+    grid_size: &TilemapGridSize,
+    z: f32,
+) -> Transform {
+    let map_width = map.width as f32 * grid_size.x;
+    let map_height = map.height as f32 * grid_size.y;
+
+    // the tilemap's origin is the center of its bottom left tile
+    Transform::from_xyz(
+        -map_width / 2.0 + bounds.origin.x as f32 * grid_size.x + grid_size.x / 2.0,
+        map_height / 2.0 - (bounds.origin.y + bounds.size.y as i32) as f32 * grid_size.y
+            + grid_size.y / 2.0,
+        z,
+    )
+}
+
 pub fn process_loaded_maps(
     mut commands: Commands,
     mut map_events: EventReader<AssetEvent<TiledMap>>,
     maps: Res<Assets<TiledMap>>,
     tile_storage_query: Query<(Entity, &TileStorage)>,
     mut map_query: Query<(
+        Entity,
         &Handle<TiledMap>,
         &mut TiledLayersStorage,
         &TilemapRenderSettings,
@@ -223,7 +493,7 @@ pub fn process_loaded_maps(
                 log::info!("Map removed!");
                 // if mesh was modified and removed in the same update, ignore the modification
                 // events are ordered so future modification events are ok
-                changed_maps.retain(|changed_handle| changed_handle == id);
+                changed_maps.retain(|changed_handle| changed_handle != id);
             }
             _ => continue,
         }
@@ -235,21 +505,16 @@ pub fn process_loaded_maps(
     }
 
     for changed_map in changed_maps.iter() {
-        for (map_handle, mut layer_storage, render_settings) in map_query.iter_mut() {
+        for (map_entity, map_handle, mut layer_storage, render_settings) in map_query.iter_mut() {
             // only deal with currently changed map
             if map_handle.id() != *changed_map {
                 continue;
             }
             if let Some(tiled_map) = maps.get(map_handle) {
-                // TODO: Create a RemoveMap component..
-                for layer_entity in layer_storage.storage.values() {
-                    if let Ok((_, layer_tile_storage)) = tile_storage_query.get(*layer_entity) {
-                        for tile in layer_tile_storage.iter().flatten() {
-                            commands.entity(*tile).despawn_recursive()
-                        }
-                    }
-                    // commands.entity(*layer_entity).despawn_recursive();
-                }
+                // Replace everything that was spawned for the previous version of the map, the
+                // player isn't part of the map so it keeps its position and state.
+                despawn_layers(&mut commands, &layer_storage, &tile_storage_query);
+                layer_storage.storage.clear();
 
                 // The TilemapBundle requires that all tile images come exclusively from a single
                 // tiled texture or from a Vec of independent per-tile images. Furthermore, all of
@@ -286,18 +551,11 @@ pub fn process_loaded_maps(
                             continue;
                         };
 
-                        let tiled::TileLayer::Finite(layer_data) = tile_layer else {
-                            log::info!(
-                                "Skipping layer {} because only finite layers are supported.",
-                                layer.id()
-                            );
+                        let Some(bounds) = layer_bounds(&tiled_map.map, &tile_layer) else {
+                            log::info!("Skipping layer {} because it is empty.", layer.id());
                             continue;
                         };
-
-                        let map_size = TilemapSize {
-                            x: tiled_map.map.width,
-                            y: tiled_map.map.height,
-                        };
+                        let map_size = bounds.size;
 
                         let grid_size = TilemapGridSize {
                             x: tiled_map.map.tile_width as f32,
@@ -320,57 +578,136 @@ pub fn process_loaded_maps(
                         let mut tile_storage = TileStorage::empty(map_size);
                         let layer_entity = commands.spawn_empty().id();
 
-                        for x in 0..map_size.x {
-                            for y in 0..map_size.y {
-                                // Transform TMX coords into bevy coords.
-                                let mapped_y = tiled_map.map.height - 1 - y;
-
-                                let mapped_x = x as i32;
-                                let mapped_y = mapped_y as i32;
+                        let mut chunk_colliders = HashMap::<(IVec2, bool), Vec<_>>::default();
 
-                                let layer_tile = match layer_data.get_tile(mapped_x, mapped_y) {
-                                    Some(t) => t,
+                        for coords in layer_tile_coords(&tile_layer, &bounds) {
+                            let layer_tile = match tile_layer.get_tile(coords.x, coords.y) {
+                                Some(t) => t,
+                                None => {
+                                    continue;
+                                }
+                            };
+                            if tileset_index != layer_tile.tileset_index() {
+                                continue;
+                            }
+                            let layer_tile_data =
+                                match get_tile_data(&tile_layer, coords.x, coords.y) {
+                                    Some(d) => d,
                                     None => {
                                         continue;
                                     }
                                 };
-                                if tileset_index != layer_tile.tileset_index() {
-                                    continue;
-                                }
-                                let layer_tile_data =
-                                    match layer_data.get_tile_data(mapped_x, mapped_y) {
-                                        Some(d) => d,
-                                        None => {
-                                            continue;
-                                        }
-                                    };
-
-                                let texture_index = match tilemap_texture {
-                                    TilemapTexture::Single(_) => layer_tile.id(),
-                                    #[cfg(not(feature = "atlas"))]
-                                    TilemapTexture::Vector(_) =>
-                                        *tiled_map.tile_image_offsets.get(&(tileset_index, layer_tile.id()))
-                                        .expect("The offset into to image vector should have been saved during the initial load."),
-                                    #[cfg(not(feature = "atlas"))]
-                                    _ => unreachable!()
-                                };
 
-                                let tile_pos = TilePos { x, y };
-                                let tile_entity = commands
-                                    .spawn(TileBundle {
-                                        position: tile_pos,
-                                        tilemap_id: TilemapId(layer_entity),
-                                        texture_index: TileTextureIndex(texture_index),
-                                        flip: TileFlip {
-                                            x: layer_tile_data.flip_h,
-                                            y: layer_tile_data.flip_v,
-                                            d: layer_tile_data.flip_d,
-                                        },
-                                        ..Default::default()
-                                    })
-                                    .id();
-                                tile_storage.set(&tile_pos, tile_entity);
+                            let texture_index = tiled_map.texture_index(
+                                tilemap_texture,
+                                tileset_index,
+                                layer_tile.id(),
+                            );
+
+                            // Transform TMX coords into bevy coords.
+                            let tile_pos = TilePos {
+                                x: (coords.x - bounds.origin.x) as u32,
+                                y: (bounds.origin.y + map_size.y as i32 - 1 - coords.y) as u32,
+                            };
+                            let tile_entity = commands
+                                .spawn(TileBundle {
+                                    position: tile_pos,
+                                    tilemap_id: TilemapId(layer_entity),
+                                    texture_index: TileTextureIndex(texture_index),
+                                    flip: TileFlip {
+                                        x: layer_tile_data.flip_h,
+                                        y: layer_tile_data.flip_v,
+                                        d: layer_tile_data.flip_d,
+                                    },
+                                    color: TileColor(Color::rgba(1.0, 1.0, 1.0, layer.opacity)),
+                                    ..Default::default()
+                                })
+                                .id();
+                            tile_storage.set(&tile_pos, tile_entity);
+
+                            let animation = layer_tile.get_tile().and_then(|tile| {
+                                let frames = tile.animation.as_ref()?;
+                                TileAnimation::new(frames.iter().map(|frame| {
+                                    (
+                                        tiled_map.texture_index(
+                                            tilemap_texture,
+                                            tileset_index,
+                                            frame.tile_id,
+                                        ),
+                                        frame.duration as f32 / 1000.0,
+                                    )
+                                }))
+                            });
+                            if let Some(animation) = animation {
+                                commands.entity(tile_entity).insert(animation);
+                            }
+                            if let Some(tile) = layer_tile.get_tile() {
+                                properties::insert_tiled_components(
+                                    &mut commands,
+                                    tile_entity,
+                                    &tile.properties,
+                                );
                             }
+
+                            let one_way = has_bool_property(&layer_tile, ONE_WAY_PROPERTY);
+                            if one_way || has_bool_property(&layer_tile, SOLID_PROPERTY) {
+                                chunk_colliders
+                                    .entry((coords.div_euclid(CHUNK_SIZE), one_way))
+                                    .or_default()
+                                    .push(tile_collider(&tile_pos, &grid_size, &map_type));
+                            }
+                        }
+
+                        // Parallax layers move with the camera, colliders would follow them and
+                        // drift away from the level geometry.
+                        let has_parallax = layer.parallax_x != 1.0 || layer.parallax_y != 1.0;
+                        if has_parallax && !chunk_colliders.is_empty() {
+                            log::warn!(
+                                "Skipping colliders of layer {} because it has a parallax factor.",
+                                layer.id()
+                            );
+                            chunk_colliders.clear();
+                        }
+
+                        // One compound collider per chunk instead of one entity per tile, one-way
+                        // tiles get their own collider so the physics hooks only filter them.
+                        for ((_, one_way), shapes) in chunk_colliders {
+                            let collider = commands
+                                .spawn((
+                                    Name::new("Tile colliders"),
+                                    TileColliders { one_way },
+                                    TransformBundle::default(),
+                                    RigidBody::Fixed,
+                                    Collider::compound(shapes),
+                                ))
+                                .id();
+                            commands.entity(layer_entity).add_child(collider);
+                        }
+
+                        let layer_transform = match tile_layer {
+                            tiled::TileLayer::Finite(_) => get_tilemap_center_transform(
+                                &map_size,
+                                &grid_size,
+                                &map_type,
+                                layer_index as f32,
+                            ),
+                            tiled::TileLayer::Infinite(_) => infinite_layer_transform(
+                                &tiled_map.map,
+                                &bounds,
+                                &grid_size,
+                                layer_index as f32,
+                            ),
+                        };
+
+                        let layer_transform =
+                            layer_transform * Transform::from_xyz(offset_x, -offset_y, 0.0);
+
+                        if let Some(parallax) = Parallax::from_layer(
+                            &tiled_map.map,
+                            &layer,
+                            layer_transform.translation,
+                        ) {
+                            commands.entity(layer_entity).insert(parallax);
                         }
 
                         commands.entity(layer_entity).insert(TilemapBundle {
@@ -380,22 +717,95 @@ pub fn process_loaded_maps(
                             texture: tilemap_texture.clone(),
                             tile_size,
                             spacing: tile_spacing,
-                            transform: get_tilemap_center_transform(
-                                &map_size,
-                                &grid_size,
-                                &map_type,
-                                layer_index as f32,
-                            ) * Transform::from_xyz(offset_x, -offset_y, 0.0),
+                            transform: layer_transform,
                             map_type,
                             render_settings: *render_settings,
                             ..Default::default()
                         });
+                        properties::insert_tiled_components(
+                            &mut commands,
+                            layer_entity,
+                            &layer.properties,
+                        );
+
+                        commands.entity(layer_entity).insert(TiledLayer {
+                            index: layer_index as u32,
+                            opacity: layer.opacity,
+                            properties: layer.properties.clone(),
+                        });
 
                         layer_storage
                             .storage
-                            .insert(layer_index as u32, layer_entity);
+                            .entry(layer_index as u32)
+                            .or_default()
+                            .push(layer_entity);
                     }
                 }
+
+                // Object layers don't depend on a tileset, spawn them once per map.
+                for (layer_index, layer) in tiled_map.map.layers().enumerate() {
+                    let tiled::LayerType::Objects(object_layer) = layer.layer_type() else {
+                        continue;
+                    };
+
+                    let layer_entity = commands
+                        .spawn((Name::new(layer.name.clone()), SpatialBundle::default()))
+                        .id();
+                    objects::spawn_object_layer(
+                        &mut commands,
+                        layer_entity,
+                        &tiled_map.map,
+                        &layer,
+                        &object_layer,
+                        layer_index as f32,
+                    );
+                    commands.entity(map_entity).add_child(layer_entity);
+                    properties::insert_tiled_components(
+                        &mut commands,
+                        layer_entity,
+                        &layer.properties,
+                    );
+
+                    layer_storage
+                        .storage
+                        .entry(layer_index as u32)
+                        .or_default()
+                        .push(layer_entity);
+                }
+
+                for (layer_index, layer) in tiled_map.map.layers().enumerate() {
+                    let tiled::LayerType::Image(image_layer) = layer.layer_type() else {
+                        continue;
+                    };
+                    let (Some(image), Some(texture)) = (
+                        &image_layer.image,
+                        tiled_map.image_layer_textures.get(&layer_index),
+                    ) else {
+                        log::info!("Skipping image layer {} without an image.", layer.id());
+                        continue;
+                    };
+
+                    let layer_entity = parallax::spawn_image_layer(
+                        &mut commands,
+                        &tiled_map.map,
+                        &layer,
+                        image,
+                        texture.clone(),
+                        layer_index as f32,
+                    );
+                    commands.entity(map_entity).add_child(layer_entity);
+                    properties::insert_tiled_components(
+                        &mut commands,
+                        layer_entity,
+                        &layer.properties,
+                    );
+
+                    layer_storage
+                        .storage
+                        .entry(layer_index as u32)
+                        .or_default()
+                        .push(layer_entity);
+                }
             }
         }
     }
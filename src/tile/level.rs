@@ -0,0 +1,160 @@
+// gestion des niveaux : un niveau est une map Tiled, on ne garde qu'un seul niveau chargé a la
+// fois, les autres modules demandent un changement de niveau via la ressource LevelManager
+
+use bevy::{log, prelude::*};
+use bevy_ecs_tilemap::prelude::TileStorage;
+use bevy_rapier2d::prelude::Velocity;
+
+use crate::player::Player;
+use crate::room::Checkpoint;
+use crate::tile::helpers::{self, TiledLayersStorage, TiledMap, TiledMapBundle};
+use crate::tile::objects::TiledObject;
+
+/// Niveau chargé au lancement du jeu
+pub const FIRST_LEVEL: &str = "map.tmx";
+
+struct CurrentLevel {
+    path: String,
+    entity: Entity,
+    loaded: bool,
+}
+
+/// Charge et décharge les maps, les demandes sont traitées au prochain update
+#[derive(Resource, Default)]
+pub struct LevelManager {
+    current: Option<CurrentLevel>,
+    /// Chemin du niveau a charger
+    request: Option<String>,
+}
+
+impl LevelManager {
+    /// Remplace le niveau actuel par la map au chemin donné (relatif au dossier assets)
+    pub fn load_level(&mut self, path: impl Into<String>) {
+        self.request = Some(path.into());
+    }
+
+    /// Chemin du niveau actuel
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_ref().map(|level| level.path.as_str())
+    }
+}
+
+/// Envoyé quand toutes les couches de la map d'un niveau ont été créées
+#[derive(Event)]
+pub struct LevelLoaded;
+
+/// Envoyé quand un niveau a été déchargé
+#[derive(Event)]
+pub struct LevelUnloaded;
+
+/// Point de spawn du joueur dans un niveau (objet de classe "spawn")
+#[derive(Component)]
+pub struct SpawnPoint;
+
+pub fn load_first_level(mut levels: ResMut<LevelManager>) {
+    levels.load_level(FIRST_LEVEL);
+}
+
+/// Traite les demandes de chargement, le niveau actuel est déchargé avant
+pub fn process_level_requests(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut levels: ResMut<LevelManager>,
+    mut unloaded: EventWriter<LevelUnloaded>,
+    maps: Query<&TiledLayersStorage>,
+    tile_storage_query: Query<(Entity, &TileStorage)>,
+) {
+    let Some(path) = levels.request.take() else {
+        return;
+    };
+
+    if let Some(level) = levels.current.take() {
+        if let Ok(layer_storage) = maps.get(level.entity) {
+            helpers::despawn_layers(&mut commands, layer_storage, &tile_storage_query);
+        }
+        commands.entity(level.entity).despawn_recursive();
+
+        log::info!("Unloaded level {}", level.path);
+        unloaded.send(LevelUnloaded);
+    }
+
+    let map_handle: Handle<TiledMap> = asset_server.load(path.clone());
+
+    let entity = commands
+        .spawn((
+            Name::new(path.clone()),
+            TiledMapBundle {
+                tiled_map: map_handle,
+                ..Default::default()
+            },
+        ))
+        .id();
+
+    levels.current = Some(CurrentLevel {
+        path,
+        entity,
+        loaded: false,
+    });
+}
+
+/// Prévient les autres modules quand les couches du niveau ont été créées
+pub fn detect_level_loaded(
+    mut levels: ResMut<LevelManager>,
+    mut loaded: EventWriter<LevelLoaded>,
+    maps: Query<&TiledLayersStorage>,
+) {
+    let Some(level) = levels.current.as_mut() else {
+        return;
+    };
+
+    if level.loaded {
+        return;
+    }
+
+    let Ok(layer_storage) = maps.get(level.entity) else {
+        return;
+    };
+
+    if !layer_storage.storage.is_empty() {
+        level.loaded = true;
+
+        log::info!("Loaded level {}", level.path);
+        loaded.send(LevelLoaded);
+    }
+}
+
+pub fn setup_spawn_points(
+    mut commands: Commands,
+    query: Query<(Entity, &TiledObject), Added<TiledObject>>,
+) {
+    for (entity, object) in &query {
+        if object.is("spawn") {
+            commands.entity(entity).insert(SpawnPoint);
+        }
+    }
+}
+
+/// Place le joueur sur le point de spawn du niveau qui vient d'être chargé
+pub fn place_player(
+    mut events: EventReader<LevelLoaded>,
+    mut checkpoint: ResMut<Checkpoint>,
+    spawn_points: Query<&GlobalTransform, With<SpawnPoint>>,
+    mut player: Query<(&mut Transform, &mut Velocity), With<Player>>,
+) {
+    if events.read().last().is_none() || player.is_empty() {
+        return;
+    }
+
+    let Some(spawn) = spawn_points.iter().next() else {
+        log::warn!("No spawn point in level, the player stays in place");
+        return;
+    };
+
+    let position = spawn.translation().truncate();
+    let (mut transform, mut velocity) = player.single_mut();
+
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+    velocity.linvel = Vec2::ZERO;
+    checkpoint.0 = position;
+}
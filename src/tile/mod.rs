@@ -1,33 +1,42 @@
 use bevy::prelude::*;
-use bevy_ecs_tilemap::tiles::TilePos;
-use bevy_rapier2d::prelude::*;
+
+use crate::camera::CameraSystem;
 
 pub struct TileWorldPlugin;
 
+pub mod animation;
 mod helpers;
+pub mod level;
+pub mod objects;
+pub mod parallax;
+pub mod properties;
+
+pub use helpers::{TileColliders, TiledLayer};
+pub use level::{LevelLoaded, LevelManager, LevelUnloaded};
 
 impl Plugin for TileWorldPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(helpers::TiledMapPlugin)
-            .add_systems(Startup, (setup_tiled_map, setup_colliders).chain());
-    }
-}
-
-fn setup_tiled_map(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let map_handle: Handle<helpers::TiledMap> = asset_server.load("map.tmx");
-
-    commands.spawn(helpers::TiledMapBundle {
-        tiled_map: map_handle,
-        ..Default::default()
-    });
-}
-
-fn setup_colliders(mut commands: Commands, mut query: Query<(Entity, &TilePos)>) {
-    if query.is_empty() {
-        return;
-    }
-
-    for (entity, mut tile) in &mut query {
-        commands.entity(entity).insert(Collider::cuboid(0.5, 0.5));
+            .init_resource::<LevelManager>()
+            .add_event::<LevelLoaded>()
+            .add_event::<LevelUnloaded>()
+            .add_systems(Startup, level::load_first_level)
+            .add_systems(
+                Update,
+                (
+                    level::process_level_requests.before(helpers::process_loaded_maps),
+                    (level::setup_spawn_points, level::place_player).chain(),
+                    animation::animate_tiles,
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    level::detect_level_loaded,
+                    parallax::update_parallax
+                        .after(CameraSystem::Follow)
+                        .before(CameraSystem::Offset),
+                ),
+            );
     }
 }
@@ -0,0 +1,166 @@
+// Les objets des calques d'objets de Tiled sont spawn comme des entités génériques avec un
+// component TiledObject, chaque module du jeu peut ensuite réagir a l'ajout de ces entités
+// (Added<TiledObject>) pour leur rajouter ses propres components selon la classe de l'objet
+
+use bevy::{log, prelude::*};
+
+use crate::tile::properties;
+
+/// Forme d'un objet Tiled, les points sont relatifs a la position de l'entité
+#[derive(Clone, Debug)]
+pub enum TiledObjectShape {
+    Rect,
+    Ellipse,
+    Point,
+    Polyline(Vec<Vec2>),
+    Polygon(Vec<Vec2>),
+}
+
+/// Objet lu depuis un calque d'objets de Tiled
+#[derive(Component, Clone)]
+pub struct TiledObject {
+    /// Id unique de l'objet dans la map, utilisé pour référencer les objets entre eux
+    pub id: u32,
+    pub name: String,
+    /// Classe (ou type) de l'objet dans Tiled
+    pub class: String,
+    /// Nom du calque qui contient l'objet
+    pub layer: String,
+    /// Taille de l'objet en pixels, nulle pour les points et les lignes
+    pub size: Vec2,
+    pub shape: TiledObjectShape,
+    pub properties: tiled::Properties,
+}
+
+impl TiledObject {
+    /// La classe de l'objet, ou a défaut le nom de son calque, permet de retrouver les objets
+    /// d'un certain type quelle que soit la manière dont ils ont été rangés dans Tiled
+    pub fn is(&self, kind: &str) -> bool {
+        self.class.eq_ignore_ascii_case(kind) || self.layer.eq_ignore_ascii_case(kind)
+    }
+
+    pub fn bool_property(&self, name: &str) -> Option<bool> {
+        match self.properties.get(name)? {
+            tiled::PropertyValue::BoolValue(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn float_property(&self, name: &str) -> Option<f32> {
+        match self.properties.get(name)? {
+            tiled::PropertyValue::FloatValue(value) => Some(*value),
+            tiled::PropertyValue::IntValue(value) => Some(*value as f32),
+            _ => None,
+        }
+    }
+
+    pub fn string_property(&self, name: &str) -> Option<&str> {
+        match self.properties.get(name)? {
+            tiled::PropertyValue::StringValue(value) => Some(value.as_str()),
+            tiled::PropertyValue::FileValue(value) => Some(value.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Id d'un objet référencé par une propriété de type "object"
+    pub fn object_property(&self, name: &str) -> Option<u32> {
+        match self.properties.get(name)? {
+            tiled::PropertyValue::ObjectValue(0) => None,
+            tiled::PropertyValue::ObjectValue(id) => Some(*id),
+            tiled::PropertyValue::IntValue(id) => Some(*id as u32),
+            _ => None,
+        }
+    }
+}
+
+/// Rectangle occupé par un objet dans le monde, a partir de son GlobalTransform
+pub fn object_rect(object: &TiledObject, transform: &GlobalTransform) -> Rect {
+    Rect::from_center_size(transform.translation().truncate(), object.size)
+}
+
+/// Convertit une position Tiled (origine en haut a gauche, y vers le bas) en position relative
+/// au centre de la map (origine au centre, y vers le haut)
+pub fn tiled_to_local(map: &tiled::Map, x: f32, y: f32) -> Vec2 {
+    let width = (map.width * map.tile_width) as f32;
+    let height = (map.height * map.tile_height) as f32;
+
+    Vec2::new(x - width / 2.0, height / 2.0 - y)
+}
+
+/// Fait spawn les objets d'un calque d'objets comme enfants de l'entité du calque
+pub fn spawn_object_layer(
+    commands: &mut Commands,
+    layer_entity: Entity,
+    map: &tiled::Map,
+    layer: &tiled::Layer,
+    object_layer: &tiled::ObjectLayer,
+    z: f32,
+) {
+    let layer_offset = Vec2::new(layer.offset_x, -layer.offset_y);
+
+    for object in object_layer.objects() {
+        let (size, shape) = match &object.shape {
+            tiled::ObjectShape::Rect { width, height } => {
+                (Vec2::new(*width, *height), TiledObjectShape::Rect)
+            }
+            tiled::ObjectShape::Ellipse { width, height } => {
+                (Vec2::new(*width, *height), TiledObjectShape::Ellipse)
+            }
+            tiled::ObjectShape::Point(_, _) => (Vec2::ZERO, TiledObjectShape::Point),
+            tiled::ObjectShape::Polyline { points } => (
+                Vec2::ZERO,
+                TiledObjectShape::Polyline(
+                    points.iter().map(|(x, y)| Vec2::new(*x, -*y)).collect(),
+                ),
+            ),
+            tiled::ObjectShape::Polygon { points } => (
+                Vec2::ZERO,
+                TiledObjectShape::Polygon(points.iter().map(|(x, y)| Vec2::new(*x, -*y)).collect()),
+            ),
+            _ => {
+                log::info!("Skipping object {} with unsupported shape.", object.id());
+                continue;
+            }
+        };
+
+        // les rectangles sont positionnés depuis leur coin haut gauche, sauf les objets tuiles
+        // qui sont positionnés depuis leur coin bas gauche, on place l'entité au centre
+        let center = if object.tile_data().is_some() {
+            Vec2::new(object.x + size.x / 2.0, object.y - size.y / 2.0)
+        } else {
+            Vec2::new(object.x + size.x / 2.0, object.y + size.y / 2.0)
+        };
+        let position = tiled_to_local(map, center.x, center.y) + layer_offset;
+
+        let object_entity = commands
+            .spawn((
+                Name::new(if object.name.is_empty() {
+                    format!("Object {}", object.id())
+                } else {
+                    object.name.clone()
+                }),
+                TiledObject {
+                    id: object.id(),
+                    name: object.name.clone(),
+                    class: object.user_type.clone(),
+                    layer: layer.name.clone(),
+                    size,
+                    shape,
+                    properties: object.properties.clone(),
+                },
+                SpatialBundle {
+                    transform: Transform::from_translation(position.extend(z)),
+                    visibility: if object.visible {
+                        Visibility::Inherited
+                    } else {
+                        Visibility::Hidden
+                    },
+                    ..Default::default()
+                },
+            ))
+            .id();
+
+        commands.entity(layer_entity).add_child(object_entity);
+        properties::insert_tiled_components(commands, object_entity, &object.properties);
+    }
+}
@@ -0,0 +1,153 @@
+// calques d'images de Tiled et parallaxe : les calques dont le facteur de parallaxe (parallaxx,
+// parallaxy dans Tiled) n'est pas 1 se déplacent moins vite (ou plus vite) que la caméra
+//
+// propriétés reconnues sur les calques d'images :
+// - repeat_x, repeat_y (bool) : l'image est répétée sur cet axe pour couvrir toute la vue
+
+use bevy::prelude::*;
+use bevy::sprite::{Anchor, ImageScaleMode};
+
+use crate::camera::PlayerCamera;
+use crate::tile::objects::tiled_to_local;
+
+/// Déplace un calque par rapport a la caméra comme le fait Tiled
+#[derive(Component)]
+pub struct Parallax {
+    /// 1 suit le monde, 0 reste fixe a l'écran
+    pub factor: Vec2,
+    /// Position du calque quand la caméra est sur l'origine de la parallaxe
+    pub base: Vec3,
+    /// Origine de la parallaxe, le coin haut gauche de la map comme dans Tiled
+    pub origin: Vec2,
+    pub repeat: BVec2,
+    /// Taille de l'image répétée, nulle pour les calques de tuiles
+    pub image_size: Vec2,
+}
+
+impl Parallax {
+    /// Parallaxe d'un calque, None si le calque suit simplement le monde
+    pub fn from_layer(map: &tiled::Map, layer: &tiled::Layer, base: Vec3) -> Option<Parallax> {
+        let factor = Vec2::new(layer.parallax_x, layer.parallax_y);
+
+        if factor == Vec2::ONE {
+            return None;
+        }
+
+        Some(Parallax {
+            factor,
+            base,
+            origin: tiled_to_local(map, 0.0, 0.0),
+            repeat: BVec2::FALSE,
+            image_size: Vec2::ZERO,
+        })
+    }
+}
+
+fn layer_bool_property(layer: &tiled::Layer, name: &str) -> bool {
+    matches!(
+        layer.properties.get(name),
+        Some(tiled::PropertyValue::BoolValue(true))
+    )
+}
+
+/// Fait spawn le sprite d'un calque d'images, avec l'opacité, le décalage et la parallaxe du
+/// calque
+pub fn spawn_image_layer(
+    commands: &mut Commands,
+    map: &tiled::Map,
+    layer: &tiled::Layer,
+    image: &tiled::Image,
+    texture: Handle<Image>,
+    z: f32,
+) -> Entity {
+    let image_size = Vec2::new(image.width as f32, image.height as f32);
+    let base = tiled_to_local(map, layer.offset_x, layer.offset_y).extend(z);
+    let repeat = BVec2::new(
+        layer_bool_property(layer, "repeat_x"),
+        layer_bool_property(layer, "repeat_y"),
+    );
+
+    let mut entity = commands.spawn((
+        Name::new(layer.name.clone()),
+        SpriteBundle {
+            texture,
+            sprite: Sprite {
+                color: Color::rgba(1.0, 1.0, 1.0, layer.opacity),
+                custom_size: Some(image_size),
+                anchor: Anchor::TopLeft,
+                ..Default::default()
+            },
+            transform: Transform::from_translation(base),
+            visibility: if layer.visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            },
+            ..Default::default()
+        },
+    ));
+
+    // la répétition a besoin de la parallaxe même si le calque suit le monde
+    if repeat.any() || Vec2::new(layer.parallax_x, layer.parallax_y) != Vec2::ONE {
+        entity.insert((
+            Parallax {
+                factor: Vec2::new(layer.parallax_x, layer.parallax_y),
+                base,
+                origin: tiled_to_local(map, 0.0, 0.0),
+                repeat,
+                image_size,
+            },
+            ImageScaleMode::Tiled {
+                tile_x: repeat.x,
+                tile_y: repeat.y,
+                stretch_value: 1.0,
+            },
+        ));
+    }
+
+    entity.id()
+}
+
+/// Place les calques selon la position de la caméra, doit tourner après le suivi de la caméra
+pub fn update_parallax(
+    camera: Query<(&Transform, &OrthographicProjection), With<PlayerCamera>>,
+    mut layers: Query<(&Parallax, &mut Transform, Option<&mut Sprite>), Without<PlayerCamera>>,
+) {
+    if camera.is_empty() {
+        return;
+    }
+
+    let (camera_transform, projection) = camera.single();
+    let camera_position = camera_transform.translation.truncate();
+    let view = projection.area.size();
+
+    for (parallax, mut transform, sprite) in &mut layers {
+        let mut position = parallax.base.truncate()
+            + (camera_position - parallax.origin) * (Vec2::ONE - parallax.factor);
+
+        if let Some(mut sprite) = sprite {
+            let size = parallax.image_size;
+            let mut covered = size;
+
+            // l'image est décalée d'un nombre entier de fois sa taille pour rester sous la vue,
+            // et agrandie pour la couvrir entièrement
+            if parallax.repeat.x && size.x > 0.0 {
+                let left = camera_position.x - view.x / 2.0;
+                position.x += ((left - position.x) / size.x).floor() * size.x;
+                covered.x = ((view.x / size.x).ceil() + 1.0) * size.x;
+            }
+            if parallax.repeat.y && size.y > 0.0 {
+                let top = camera_position.y + view.y / 2.0;
+                position.y += ((top - position.y) / size.y).ceil() * size.y;
+                covered.y = ((view.y / size.y).ceil() + 1.0) * size.y;
+            }
+
+            if parallax.repeat.any() && sprite.custom_size != Some(covered) {
+                sprite.custom_size = Some(covered);
+            }
+        }
+
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
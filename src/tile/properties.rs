@@ -0,0 +1,336 @@
+// les propriétés de Tiled (sur les tuiles, les objets et les calques) sont converties en
+// components Bevy par réflexion, le component doit être enregistré (register_type) et déclarer
+// #[reflect(Component, Default, FromTiled)], ce qui n'est pas précisé garde sa valeur par défaut
+//
+// FromTiled est explicite pour que la map ne puisse pas ajouter des components réservés a une
+// seule entité (Player, PlayerState...), les systèmes qui font single() paniqueraient
+//
+// trois façons de remplir un component :
+// - propriété de type "class" dont la classe s'appelle comme le component : ses membres
+//   remplissent les champs du même nom ("0", "1"... pour les tuple structs)
+// - propriété simple nommée comme le component ("Spike" = "left") : une string choisit la
+//   variante d'une enum, une valeur simple remplit une tuple struct a un champ
+// - propriétés simples "Component.champ" ("Surface.bounce" = 0.5) : un seul champ a la fois
+//
+// seuls les noms qui commencent par une majuscule sont regardés, les propriétés comme isSolid ou
+// direction restent pour le chargement de la map et les objets
+//
+// conversions supportées : bool, nombres, String, Color, enums (nom ou index d'une variante
+// unitaire) et structs imbriquées (propriété class dans la classe, Vec2 par exemple)
+
+use std::any::TypeId;
+use std::collections::BTreeMap;
+
+use bevy::ecs::system::Command;
+use bevy::reflect::FromType;
+use bevy::reflect::{
+    DynamicEnum, DynamicStruct, DynamicTupleStruct, DynamicVariant, ReflectRef, TypeInfo,
+    TypeRegistration, TypeRegistry,
+};
+use bevy::{log, prelude::*};
+
+/// Autorise un component a être ajouté depuis les propriétés de Tiled, a déclarer avec
+/// #[reflect(FromTiled)]
+#[derive(Clone)]
+pub struct ReflectFromTiled;
+
+impl<T> FromType<T> for ReflectFromTiled {
+    fn from_type() -> Self {
+        ReflectFromTiled
+    }
+}
+
+/// Insère les components décrits par les propriétés d'une entité de la map
+pub struct InsertTiledComponents {
+    pub entity: Entity,
+    pub properties: tiled::Properties,
+}
+
+impl Command for InsertTiledComponents {
+    fn apply(self, world: &mut World) {
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        // les propriétés "Component.champ" sont regroupées comme une classe par component
+        let mut fields = BTreeMap::<&str, tiled::Properties>::new();
+
+        for (name, value) in &self.properties {
+            if !is_component_name(name) {
+                continue;
+            }
+
+            if let Some((type_name, field)) = name.split_once('.') {
+                fields
+                    .entry(type_name)
+                    .or_default()
+                    .insert(field.to_string(), value.clone());
+                continue;
+            }
+
+            let type_name = match value {
+                tiled::PropertyValue::ClassValue { property_type, .. } => property_type.as_str(),
+                _ => name.as_str(),
+            };
+
+            insert_component(world, self.entity, &registry, name, type_name, value);
+        }
+
+        for (type_name, properties) in fields {
+            let value = tiled::PropertyValue::ClassValue {
+                property_type: type_name.to_string(),
+                properties,
+            };
+
+            insert_component(world, self.entity, &registry, type_name, type_name, &value);
+        }
+    }
+}
+
+/// Les noms de types commencent par une majuscule, pas les autres propriétés de la map
+fn is_component_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+fn find_registration<'a>(
+    registry: &'a TypeRegistry,
+    type_name: &str,
+) -> Option<&'a TypeRegistration> {
+    registry
+        .get_with_short_type_path(type_name)
+        .or_else(|| registry.get_with_type_path(type_name))
+}
+
+fn insert_component(
+    world: &mut World,
+    entity: Entity,
+    registry: &TypeRegistry,
+    name: &str,
+    type_name: &str,
+    value: &tiled::PropertyValue,
+) {
+    let is_class = matches!(value, tiled::PropertyValue::ClassValue { .. });
+
+    let Some(registration) = find_registration(registry, type_name) else {
+        // une propriété simple peut très bien ne pas être un component
+        if is_class || name.contains('.') {
+            log::warn!("Property {name}: no registered type named {type_name}");
+        }
+        return;
+    };
+
+    let (Some(reflect_component), Some(reflect_default), Some(_)) = (
+        registration.data::<ReflectComponent>(),
+        registration.data::<ReflectDefault>(),
+        registration.data::<ReflectFromTiled>(),
+    ) else {
+        log::warn!(
+            "Property {name}: {type_name} must be registered with #[reflect(Component, Default, FromTiled)]"
+        );
+        return;
+    };
+
+    let Some(patch) = component_to_reflect(registration.type_info(), value, registry) else {
+        log::warn!("Property {name}: can't convert the value to {type_name}");
+        return;
+    };
+
+    let mut component = reflect_default.default();
+    component.apply(patch.as_reflect());
+
+    let Some(mut entity) = world.get_entity_mut(entity) else {
+        return;
+    };
+    reflect_component.insert(&mut entity, component.as_reflect(), registry);
+}
+
+/// Ajoute la commande seulement si l'entité a des propriétés qui peuvent être des components,
+/// la plupart des tuiles n'en ont pas
+pub fn insert_tiled_components(
+    commands: &mut Commands,
+    entity: Entity,
+    properties: &tiled::Properties,
+) {
+    if properties.keys().any(|name| is_component_name(name)) {
+        commands.add(InsertTiledComponents {
+            entity,
+            properties: properties.clone(),
+        });
+    }
+}
+
+/// Valeur d'un component entier : une classe pour les structs, une valeur simple pour les enums
+/// et les tuple structs a un champ
+fn component_to_reflect(
+    type_info: &TypeInfo,
+    value: &tiled::PropertyValue,
+    registry: &TypeRegistry,
+) -> Option<Box<dyn Reflect>> {
+    match (value, type_info) {
+        (tiled::PropertyValue::ClassValue { properties, .. }, _) => {
+            class_to_reflect(type_info, properties, registry)
+        }
+        (_, TypeInfo::TupleStruct(info)) if info.field_len() == 1 => {
+            let field = info.field_at(0)?;
+            let field_info = registry.get_type_info(field.type_id());
+            let value = property_to_reflect(value, field.type_id(), field_info, registry)?;
+
+            let mut dynamic = DynamicTupleStruct::default();
+            dynamic.insert_boxed(value);
+            Some(Box::new(dynamic))
+        }
+        _ => property_to_reflect(value, type_info.type_id(), Some(type_info), registry),
+    }
+}
+
+/// Struct dynamique avec les membres de la classe qui correspondent a un champ du type, les
+/// membres d'une tuple struct sont nommés par leur index
+fn class_to_reflect(
+    type_info: &TypeInfo,
+    properties: &tiled::Properties,
+    registry: &TypeRegistry,
+) -> Option<Box<dyn Reflect>> {
+    match type_info {
+        TypeInfo::Struct(info) => {
+            let mut dynamic = DynamicStruct::default();
+
+            for (name, value) in properties {
+                let Some(field) = info.field(name) else {
+                    log::warn!("{} has no field named {name}", info.type_path());
+                    continue;
+                };
+
+                let field_info = registry.get_type_info(field.type_id());
+
+                match property_to_reflect(value, field.type_id(), field_info, registry) {
+                    Some(value) => dynamic.insert_boxed(name, value),
+                    None => log::warn!(
+                        "Can't convert {name} to {} for {}",
+                        field.type_path(),
+                        info.type_path()
+                    ),
+                }
+            }
+
+            Some(Box::new(dynamic))
+        }
+        TypeInfo::TupleStruct(info) => {
+            // un tuple dynamique doit avoir tous ses champs dans l'ordre, ceux qui manquent
+            // gardent la valeur par défaut du type
+            let default = registry
+                .get(info.type_id())?
+                .data::<ReflectDefault>()
+                .map(|default| default.default());
+            let default = default
+                .as_ref()
+                .and_then(|default| match default.reflect_ref() {
+                    ReflectRef::TupleStruct(tuple) => Some(tuple),
+                    _ => None,
+                });
+
+            let mut dynamic = DynamicTupleStruct::default();
+
+            for index in 0..info.field_len() {
+                let field = info.field_at(index)?;
+                let field_info = registry.get_type_info(field.type_id());
+
+                let value = properties
+                    .get(&index.to_string())
+                    .and_then(|value| {
+                        property_to_reflect(value, field.type_id(), field_info, registry)
+                    })
+                    .or_else(|| default?.field(index).map(|value| value.clone_value()));
+
+                let Some(value) = value else {
+                    log::warn!("Missing field {index} for {}", info.type_path());
+                    return None;
+                };
+                dynamic.insert_boxed(value);
+            }
+
+            Some(Box::new(dynamic))
+        }
+        _ => None,
+    }
+}
+
+fn property_to_reflect(
+    value: &tiled::PropertyValue,
+    type_id: TypeId,
+    type_info: Option<&TypeInfo>,
+    registry: &TypeRegistry,
+) -> Option<Box<dyn Reflect>> {
+    let number = match value {
+        tiled::PropertyValue::IntValue(value) => Some(*value as f64),
+        tiled::PropertyValue::FloatValue(value) => Some(*value as f64),
+        tiled::PropertyValue::ObjectValue(value) => Some(*value as f64),
+        _ => None,
+    };
+
+    if let Some(number) = number {
+        if let Some(value) = number_to_reflect(number, type_id) {
+            return Some(value);
+        }
+    }
+
+    match (value, type_info) {
+        (tiled::PropertyValue::BoolValue(value), _) if type_id == TypeId::of::<bool>() => {
+            Some(Box::new(*value))
+        }
+        (tiled::PropertyValue::StringValue(value) | tiled::PropertyValue::FileValue(value), _)
+            if type_id == TypeId::of::<String>() =>
+        {
+            Some(Box::new(value.clone()))
+        }
+        (tiled::PropertyValue::ColorValue(color), _) if type_id == TypeId::of::<Color>() => {
+            Some(Box::new(Color::rgba_u8(
+                color.red,
+                color.green,
+                color.blue,
+                color.alpha,
+            )))
+        }
+        // les enums de Tiled sont enregistrées comme des strings ou des entiers
+        (tiled::PropertyValue::StringValue(value), Some(TypeInfo::Enum(info))) => {
+            let variant = info
+                .variant_names()
+                .iter()
+                .find(|variant| variant.eq_ignore_ascii_case(value))?;
+            Some(Box::new(DynamicEnum::new(*variant, DynamicVariant::Unit)))
+        }
+        (tiled::PropertyValue::IntValue(index), Some(TypeInfo::Enum(info))) => {
+            let variant = info.variant_at(usize::try_from(*index).ok()?)?;
+            Some(Box::new(DynamicEnum::new(
+                variant.name(),
+                DynamicVariant::Unit,
+            )))
+        }
+        (tiled::PropertyValue::ClassValue { properties, .. }, Some(type_info)) => {
+            class_to_reflect(type_info, properties, registry)
+        }
+        _ => None,
+    }
+}
+
+fn number_to_reflect(number: f64, type_id: TypeId) -> Option<Box<dyn Reflect>> {
+    let value: Box<dyn Reflect> = if type_id == TypeId::of::<f32>() {
+        Box::new(number as f32)
+    } else if type_id == TypeId::of::<f64>() {
+        Box::new(number)
+    } else if type_id == TypeId::of::<i32>() {
+        Box::new(number as i32)
+    } else if type_id == TypeId::of::<i64>() {
+        Box::new(number as i64)
+    } else if type_id == TypeId::of::<u8>() {
+        Box::new(number as u8)
+    } else if type_id == TypeId::of::<u32>() {
+        Box::new(number as u32)
+    } else if type_id == TypeId::of::<u64>() {
+        Box::new(number as u64)
+    } else if type_id == TypeId::of::<usize>() {
+        Box::new(number as usize)
+    } else {
+        return None;
+    };
+
+    Some(value)
+}